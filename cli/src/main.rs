@@ -0,0 +1,321 @@
+use clap::Parser;
+use malva::{
+    Error, Syntax,
+    config::FormatOptions,
+    config_file::{ConfigFile, find_config_files, options_from_config_files},
+    detect_syntax, editorconfig, format_text,
+};
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+#[derive(Parser)]
+#[command(
+    version,
+    about = "Configurable, smart and fast CSS, SCSS, Sass and Less formatter."
+)]
+struct Args {
+    /// Files, directories or glob patterns to format.
+    /// Read from stdin if not specified or it's `-`.
+    /// Formatting multiple files requires `--write`, `--check` or `--list-different`.
+    paths: Vec<String>,
+
+    /// Write formatted code back to files.
+    #[arg(short, long)]
+    write: bool,
+
+    /// Check if files are formatted, and exit with non-zero code if not.
+    #[arg(short, long, conflicts_with = "write")]
+    check: bool,
+
+    /// Print paths of files that are different from formatted code.
+    #[arg(short, long)]
+    list_different: bool,
+
+    /// Path to config file.
+    /// If not specified, `malva.toml` or `.malvarc.json` will be searched
+    /// from directory of each file to its ancestors.
+    /// Layout options not specified in config file are taken from `.editorconfig`.
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Path used for detecting syntax when reading from stdin.
+    #[arg(long)]
+    stdin_filepath: Option<PathBuf>,
+
+    /// Check that formatted code has the same syntax tree as the original code,
+    /// and formatting it again won't change anything.
+    #[arg(long)]
+    verify: bool,
+}
+
+#[derive(Default)]
+struct Summary {
+    has_errors: bool,
+    has_different: bool,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let mut configs = match &args.config {
+        Some(path) => match ConfigFile::load(path) {
+            Ok(config) => Configs::Specified(config),
+            Err(error) => {
+                eprintln!("{error}");
+                return ExitCode::from(2);
+            }
+        },
+        None => Configs::Discovered(HashMap::new()),
+    };
+
+    let mut summary = Summary::default();
+    if args.paths.is_empty() || args.paths.iter().all(|path| path == "-") {
+        format_stdin(&args, &mut configs, &mut summary);
+    } else if args.paths.iter().any(|path| path == "-") {
+        eprintln!("`-` for stdin can't be mixed with other paths");
+        summary.has_errors = true;
+    } else {
+        let files = collect_files(&args.paths, &mut summary);
+        if files.is_empty() && !summary.has_errors {
+            eprintln!("no files found");
+            summary.has_errors = true;
+        } else if files.len() > 1 && !args.write && !args.check && !args.list_different {
+            // formatted code of different files can't be told apart in stdout
+            eprintln!(
+                "formatting multiple files requires `--write`, `--check` or `--list-different`"
+            );
+            summary.has_errors = true;
+        } else {
+            for file in files {
+                format_file(&file, &args, &mut configs, &mut summary);
+            }
+        }
+    }
+
+    if summary.has_errors {
+        ExitCode::from(2)
+    } else if summary.has_different && (args.check || args.list_different) {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+enum Configs {
+    /// Config file specified by `--config` is used for all files.
+    Specified(ConfigFile),
+    /// Loaded config files, keyed by their paths.
+    Discovered(HashMap<PathBuf, ConfigFile>),
+}
+
+impl Configs {
+    fn options_for(&mut self, file: &Path) -> Result<FormatOptions, Error> {
+        let editorconfig = editorconfig::layout_options(file);
+        match self {
+            Configs::Specified(config) => config.options_with_fallback_for(file, &editorconfig),
+            Configs::Discovered(configs) => {
+                let mut paths = vec![];
+                for path in find_config_files(file) {
+                    let config = match configs.entry(path.clone()) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => {
+                            let config = ConfigFile::load(entry.key())?;
+                            entry.insert(config)
+                        }
+                    };
+                    let is_root = config.is_root();
+                    paths.push(path);
+                    if is_root {
+                        break;
+                    }
+                }
+                options_from_config_files(
+                    paths.iter().map(|path| &configs[path]),
+                    file,
+                    &editorconfig,
+                )
+            }
+        }
+    }
+}
+
+fn format_stdin(args: &Args, configs: &mut Configs, summary: &mut Summary) {
+    let syntax = match &args.stdin_filepath {
+        Some(path) => {
+            if let Some(syntax) = detect_syntax(path) {
+                syntax
+            } else {
+                eprintln!("unknown file extension of file: {}", path.display());
+                summary.has_errors = true;
+                return;
+            }
+        }
+        None => Syntax::Css,
+    };
+    let mut input = String::new();
+    if let Err(error) = io::stdin().read_to_string(&mut input) {
+        eprintln!("failed to read stdin: {error}");
+        summary.has_errors = true;
+        return;
+    }
+
+    let name = args
+        .stdin_filepath
+        .as_deref()
+        .unwrap_or(Path::new("<stdin>"));
+    let options = match configs.options_for(name) {
+        Ok(options) => FormatOptions {
+            verify: options.verify || args.verify,
+            ..options
+        },
+        Err(error) => {
+            eprintln!("{error}");
+            summary.has_errors = true;
+            return;
+        }
+    };
+    match format_text(&input, syntax, &options) {
+        Ok(output) => {
+            if output != input {
+                summary.has_different = true;
+            }
+            if !args.check && !args.list_different {
+                print!("{output}");
+            } else if output != input {
+                println!("{}", name.display());
+            }
+        }
+        Err(error) => {
+            eprint!("{}: {}", name.display(), error.render(&input));
+            summary.has_errors = true;
+        }
+    }
+}
+
+fn format_file(path: &Path, args: &Args, configs: &mut Configs, summary: &mut Summary) {
+    let Some(syntax) = detect_syntax(path) else {
+        eprintln!("unknown file extension of file: {}", path.display());
+        summary.has_errors = true;
+        return;
+    };
+    let input = match fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("failed to read '{}': {error}", path.display());
+            summary.has_errors = true;
+            return;
+        }
+    };
+
+    let options = match configs.options_for(path) {
+        Ok(options) => FormatOptions {
+            verify: options.verify || args.verify,
+            ..options
+        },
+        Err(error) => {
+            eprintln!("{error}");
+            summary.has_errors = true;
+            return;
+        }
+    };
+    let output = match format_text(&input, syntax, &options) {
+        Ok(output) => output,
+        Err(error) => {
+            eprint!("{}: {}", path.display(), error.render(&input));
+            summary.has_errors = true;
+            return;
+        }
+    };
+    let is_different = output != input;
+    summary.has_different |= is_different;
+
+    if (args.check || args.list_different) && is_different {
+        println!("{}", path.display());
+    }
+    if args.write {
+        if is_different && let Err(error) = fs::write(path, output) {
+            eprintln!("failed to write '{}': {error}", path.display());
+            summary.has_errors = true;
+        }
+    } else if !args.check && !args.list_different {
+        let _ = io::stdout().write_all(output.as_bytes());
+    }
+}
+
+/// Expand paths into files.
+/// Directories will be walked recursively, and other paths will be treated as glob patterns.
+fn collect_files(paths: &[String], summary: &mut Summary) -> Vec<PathBuf> {
+    let mut files = vec![];
+    for path in paths {
+        let path_buf = PathBuf::from(path);
+        if path_buf.is_dir() {
+            walk_dir(&path_buf, &mut files, summary);
+        } else if path_buf.is_file() {
+            files.push(path_buf);
+        } else {
+            match glob::glob(path) {
+                Ok(entries) => {
+                    for entry in entries {
+                        match entry {
+                            Ok(path) if path.is_file() && detect_syntax(&path).is_some() => {
+                                files.push(path);
+                            }
+                            Ok(_) => {}
+                            Err(error) => {
+                                eprintln!("{error}");
+                                summary.has_errors = true;
+                            }
+                        }
+                    }
+                }
+                Err(error) => {
+                    eprintln!("invalid pattern '{path}': {error}");
+                    summary.has_errors = true;
+                }
+            }
+        }
+    }
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn walk_dir(dir: &Path, files: &mut Vec<PathBuf>, summary: &mut Summary) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!("failed to read '{}': {error}", dir.display());
+            summary.has_errors = true;
+            return;
+        }
+    };
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => {
+                eprintln!("failed to read '{}': {error}", dir.display());
+                summary.has_errors = true;
+                continue;
+            }
+        };
+        let path = entry.path();
+        let is_ignored = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.starts_with('.') || name == "node_modules");
+        if is_ignored {
+            continue;
+        }
+        // symlinked directories are skipped, since they may form a loop
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            walk_dir(&path, files, summary);
+        } else if !path.is_dir() && detect_syntax(&path).is_some() {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    fs,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+fn malva() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_malva"))
+}
+
+#[test]
+fn stdin_to_stdout() {
+    let mut child = malva()
+        .args(["--stdin-filepath", "a.scss"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"a{b{color:red}}")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "a {\n  b {\n    color: red;\n  }\n}\n"
+    );
+}
+
+#[test]
+fn check_and_write() {
+    let dir = std::env::temp_dir().join(format!("malva_cli_test_{}", std::process::id()));
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::write(dir.join("nested/a.css"), "a{color:red}").unwrap();
+    fs::write(dir.join("b.less"), "b {\n  color: red;\n}\n").unwrap();
+
+    let output = malva()
+        .arg("--check")
+        .arg(&dir)
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("a.css"));
+    assert!(!stdout.contains("b.less"));
+
+    let output = malva()
+        .arg("--write")
+        .arg(&dir)
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(dir.join("nested/a.css")).unwrap(),
+        "a {\n  color: red;\n}\n"
+    );
+
+    let output = malva()
+        .arg("--check")
+        .arg(&dir)
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn syntax_error() {
+    let mut child = malva()
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"a{}}").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("<stdin>: error: syntax error")
+    );
+}
+
+#[test]
+fn discover_config() {
+    let dir = std::env::temp_dir().join(format!("malva_cli_config_test_{}", std::process::id()));
+    fs::create_dir_all(dir.join("legacy")).unwrap();
+    fs::write(
+        dir.join("malva.toml"),
+        "indentWidth = 4\n[[overrides]]\nfiles = \"legacy/*.css\"\noptions = { indentWidth = 8 }\n",
+    )
+    .unwrap();
+    fs::write(dir.join("a.css"), "a{color:red}").unwrap();
+    fs::write(dir.join("legacy/b.css"), "b{color:red}").unwrap();
+
+    let output = malva().arg(dir.join("a.css")).output().unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "a {\n    color: red;\n}\n"
+    );
+    let output = malva().arg(dir.join("legacy/b.css")).output().unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "b {\n        color: red;\n}\n"
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn invalid_paths() {
+    let dir = std::env::temp_dir().join(format!("malva_cli_paths_test_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("a.css"), "a{color:red}").unwrap();
+    fs::write(dir.join("b.css"), "b{color:red}").unwrap();
+
+    let output = malva().arg("-").arg(dir.join("a.css")).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("can't be mixed")
+    );
+
+    let output = malva().arg(&dir).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        fs::read_to_string(dir.join("a.css")).unwrap(),
+        "a{color:red}"
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn symlink_loop() {
+    let dir = std::env::temp_dir().join(format!("malva_cli_symlink_test_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("a.css"), "a{color:red}").unwrap();
+    std::os::unix::fs::symlink(&dir, dir.join("loop")).unwrap();
+
+    let output = malva().arg("--check").arg(&dir).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 1);
+
+    fs::remove_dir_all(dir).unwrap();
+}
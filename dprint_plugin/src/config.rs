@@ -1,6 +1,6 @@
 use dprint_core::{
     configuration::{
-        ConfigKeyMap, ConfigurationDiagnostic, GlobalConfiguration, NewLineKind,
+        ConfigKeyMap, ConfigKeyValue, ConfigurationDiagnostic, GlobalConfiguration, NewLineKind,
         get_nullable_value, get_unknown_property_diagnostics, get_value,
     },
     plugins::{FileMatchingInfo, PluginResolveConfigurationResult},
@@ -12,25 +12,85 @@ pub(crate) fn resolve_config(
     global_config: &GlobalConfiguration,
 ) -> PluginResolveConfigurationResult<FormatOptions> {
     let mut diagnostics = Vec::new();
+    let syntax_configs = ["css", "scss", "sass", "less"].map(|key| (key, config.shift_remove(key)));
+    let base_config = config.clone();
+    let mut malva_config = resolve_format_options(config, global_config, &mut diagnostics);
+
+    for (key, value) in syntax_configs {
+        match value {
+            Some(ConfigKeyValue::Object(mut overrides)) => {
+                if overrides.shift_remove("verify").is_some() {
+                    diagnostics.push(ConfigurationDiagnostic {
+                        property_name: format!("{key}.verify"),
+                        message: "`verify` can't be overridden for specific syntax".into(),
+                    });
+                }
+                // options of specific syntax are resolved based on the base config
+                let keys = overrides.keys().cloned().collect::<Vec<_>>();
+                let mut merged = base_config.clone();
+                merged.extend(overrides);
+                let mut syntax_diagnostics = Vec::new();
+                let options =
+                    resolve_format_options(merged, global_config, &mut syntax_diagnostics);
+                diagnostics.extend(
+                    syntax_diagnostics
+                        .into_iter()
+                        .filter(|diagnostic| keys.contains(&diagnostic.property_name))
+                        .map(|diagnostic| ConfigurationDiagnostic {
+                            property_name: format!("{key}.{}", diagnostic.property_name),
+                            message: diagnostic.message,
+                        }),
+                );
+                let options = Some(PartialFormatOptions::from(options));
+                match key {
+                    "css" => malva_config.css = options,
+                    "scss" => malva_config.scss = options,
+                    "sass" => malva_config.sass = options,
+                    _ => malva_config.less = options,
+                }
+            }
+            Some(ConfigKeyValue::Null) | None => {}
+            Some(_) => diagnostics.push(ConfigurationDiagnostic {
+                property_name: key.into(),
+                message: format!("config `{key}` must be an object"),
+            }),
+        }
+    }
+
+    PluginResolveConfigurationResult {
+        config: malva_config,
+        diagnostics,
+        file_matching: FileMatchingInfo {
+            file_extensions: vec!["css".into(), "scss".into(), "sass".into(), "less".into()],
+            file_names: vec![],
+        },
+    }
+}
+
+fn resolve_format_options(
+    mut config: ConfigKeyMap,
+    global_config: &GlobalConfiguration,
+    diagnostics: &mut Vec<ConfigurationDiagnostic>,
+) -> FormatOptions {
     let malva_config = FormatOptions {
         layout: LayoutOptions {
             print_width: get_value(
                 &mut config,
                 "printWidth",
                 global_config.line_width.unwrap_or(80),
-                &mut diagnostics,
+                diagnostics,
             ) as usize,
             use_tabs: get_value(
                 &mut config,
                 "useTabs",
                 global_config.use_tabs.unwrap_or_default(),
-                &mut diagnostics,
+                diagnostics,
             ),
             indent_width: get_value(
                 &mut config,
                 "indentWidth",
                 global_config.indent_width.unwrap_or(2),
-                &mut diagnostics,
+                diagnostics,
             ) as usize,
             line_break: match &*get_value(
                 &mut config,
@@ -41,7 +101,7 @@ pub(crate) fn resolve_config(
                     _ => "lf",
                 }
                 .to_string(),
-                &mut diagnostics,
+                diagnostics,
             ) {
                 "lf" => LineBreak::Lf,
                 "crlf" => LineBreak::Crlf,
@@ -55,12 +115,7 @@ pub(crate) fn resolve_config(
             },
         },
         language: LanguageOptions {
-            hex_case: match &*get_value(
-                &mut config,
-                "hexCase",
-                "lower".to_string(),
-                &mut diagnostics,
-            ) {
+            hex_case: match &*get_value(&mut config, "hexCase", "lower".to_string(), diagnostics) {
                 "ignore" => HexCase::Ignore,
                 "lower" => HexCase::Lower,
                 "upper" => HexCase::Upper,
@@ -75,7 +130,7 @@ pub(crate) fn resolve_config(
             hex_color_length: get_nullable_value::<String>(
                 &mut config,
                 "hexColorLength",
-                &mut diagnostics,
+                diagnostics,
             )
             .as_deref()
             .and_then(|value| match value {
@@ -93,7 +148,7 @@ pub(crate) fn resolve_config(
                 &mut config,
                 "quotes",
                 "alwaysDouble".to_string(),
-                &mut diagnostics,
+                diagnostics,
             ) {
                 "alwaysDouble" => Quotes::AlwaysDouble,
                 "alwaysSingle" => Quotes::AlwaysSingle,
@@ -110,7 +165,7 @@ pub(crate) fn resolve_config(
             attr_selector_quotes: get_nullable_value::<String>(
                 &mut config,
                 "attrSelector.quotes",
-                &mut diagnostics,
+                diagnostics,
             )
             .as_deref()
             .and_then(|value| match value {
@@ -130,7 +185,7 @@ pub(crate) fn resolve_config(
                 &mut config,
                 "operatorLinebreak",
                 "after".to_string(),
-                &mut diagnostics,
+                diagnostics,
             ) {
                 "before" => OperatorLineBreak::Before,
                 "after" => OperatorLineBreak::After,
@@ -146,7 +201,7 @@ pub(crate) fn resolve_config(
                 &mut config,
                 "blockSelectorLinebreak",
                 "consistent".to_string(),
-                &mut diagnostics,
+                diagnostics,
             ) {
                 "always" => BlockSelectorLineBreak::Always,
                 "consistent" => BlockSelectorLineBreak::Consistent,
@@ -163,40 +218,23 @@ pub(crate) fn resolve_config(
                 &mut config,
                 "omitNumberLeadingZero",
                 false,
-                &mut diagnostics,
+                diagnostics,
             ),
-            trailing_comma: get_value(&mut config, "trailingComma", false, &mut diagnostics),
-            format_comments: get_value(&mut config, "formatComments", false, &mut diagnostics),
-            align_comments: get_value(&mut config, "alignComments", true, &mut diagnostics),
+            trailing_comma: get_value(&mut config, "trailingComma", false, diagnostics),
+            format_comments: get_value(&mut config, "formatComments", false, diagnostics),
+            align_comments: get_value(&mut config, "alignComments", true, diagnostics),
             linebreak_in_pseudo_parens: get_value(
                 &mut config,
                 "linebreakInPseudoParens",
                 false,
-                &mut diagnostics,
+                diagnostics,
             ),
-            declaration_order: get_nullable_value::<String>(
-                &mut config,
-                "declarationOrder",
-                &mut diagnostics,
-            )
-            .as_deref()
-            .and_then(|value| match value {
-                "alphabetical" => Some(DeclarationOrder::Alphabetical),
-                "smacss" => Some(DeclarationOrder::Smacss),
-                "concentric" => Some(DeclarationOrder::Concentric),
-                _ => {
-                    diagnostics.push(ConfigurationDiagnostic {
-                        property_name: "declarationOrder".into(),
-                        message: "invalid value for config `declarationOrder`".into(),
-                    });
-                    None
-                }
-            }),
+            declaration_order: resolve_declaration_order(&mut config, diagnostics),
             declaration_order_group_by: match &*get_value(
                 &mut config,
                 "declarationOrderGroupBy",
                 "nonDeclaration".to_string(),
-                &mut diagnostics,
+                diagnostics,
             ) {
                 "nonDeclaration" => DeclarationOrderGroupBy::NonDeclaration,
                 "nonDeclarationAndEmptyLine" => DeclarationOrderGroupBy::NonDeclarationAndEmptyLine,
@@ -208,15 +246,21 @@ pub(crate) fn resolve_config(
                     Default::default()
                 }
             },
+            declaration_order_separate_groups: get_value(
+                &mut config,
+                "declarationOrderSeparateGroups",
+                false,
+                diagnostics,
+            ),
             single_line_block_threshold: get_nullable_value(
                 &mut config,
                 "singleLineBlockThreshold",
-                &mut diagnostics,
+                diagnostics,
             ),
             keyframe_selector_notation: get_nullable_value::<String>(
                 &mut config,
                 "keyframeSelectorNotation",
-                &mut diagnostics,
+                diagnostics,
             )
             .as_deref()
             .and_then(|value| match value {
@@ -234,7 +278,7 @@ pub(crate) fn resolve_config(
                 &mut config,
                 "attrValueQuotes",
                 "always".to_string(),
-                &mut diagnostics,
+                diagnostics,
             ) {
                 "always" => AttrValueQuotes::Always,
                 "ignore" => AttrValueQuotes::Ignore,
@@ -246,68 +290,68 @@ pub(crate) fn resolve_config(
                     Default::default()
                 }
             },
-            prefer_single_line: get_value(&mut config, "preferSingleLine", false, &mut diagnostics),
+            prefer_single_line: get_value(&mut config, "preferSingleLine", false, diagnostics),
             selectors_prefer_single_line: get_nullable_value(
                 &mut config,
                 "selectors.preferSingleLine",
-                &mut diagnostics,
+                diagnostics,
             ),
             function_args_prefer_single_line: get_nullable_value(
                 &mut config,
                 "functionArgs.preferSingleLine",
-                &mut diagnostics,
+                diagnostics,
             ),
             sass_content_at_rule_prefer_single_line: get_nullable_value(
                 &mut config,
                 "sassContentAtRule.preferSingleLine",
-                &mut diagnostics,
+                diagnostics,
             ),
             sass_include_at_rule_prefer_single_line: get_nullable_value(
                 &mut config,
                 "sassIncludeAtRule.preferSingleLine",
-                &mut diagnostics,
+                diagnostics,
             ),
             sass_map_prefer_single_line: get_nullable_value(
                 &mut config,
                 "sassMap.preferSingleLine",
-                &mut diagnostics,
+                diagnostics,
             ),
             sass_module_config_prefer_single_line: get_nullable_value(
                 &mut config,
                 "sassModuleConfig.preferSingleLine",
-                &mut diagnostics,
+                diagnostics,
             ),
             sass_params_prefer_single_line: get_nullable_value(
                 &mut config,
                 "sassParams.preferSingleLine",
-                &mut diagnostics,
+                diagnostics,
             ),
             less_import_options_prefer_single_line: get_nullable_value(
                 &mut config,
                 "lessImportOptions.preferSingleLine",
-                &mut diagnostics,
+                diagnostics,
             ),
             less_mixin_args_prefer_single_line: get_nullable_value(
                 &mut config,
                 "lessMixinArgs.preferSingleLine",
-                &mut diagnostics,
+                diagnostics,
             ),
             less_mixin_params_prefer_single_line: get_nullable_value(
                 &mut config,
                 "lessMixinParams.preferSingleLine",
-                &mut diagnostics,
+                diagnostics,
             ),
             single_line_top_level_declarations: get_value(
                 &mut config,
                 "singleLineTopLevelDeclarations",
                 false,
-                &mut diagnostics,
+                diagnostics,
             ),
             font_family_names: match &*get_value(
                 &mut config,
                 "fontFamilyNames",
                 "consistent".to_string(),
-                &mut diagnostics,
+                diagnostics,
             ) {
                 "consistent" => FontFamilyNames::Consistent,
                 "wrap" => FontFamilyNames::Wrap,
@@ -319,36 +363,116 @@ pub(crate) fn resolve_config(
                     Default::default()
                 }
             },
-            nth_plus_spacing: get_value(&mut config, "nthPlusSpacing", false, &mut diagnostics),
+            nth_plus_spacing: get_value(&mut config, "nthPlusSpacing", false, diagnostics),
             selector_override_comment_directive: get_value(
                 &mut config,
                 "selectorOverrideCommentDirective",
                 "malva-selector-override".into(),
-                &mut diagnostics,
+                diagnostics,
+            ),
+            options_override_comment_directive: get_value(
+                &mut config,
+                "optionsOverrideCommentDirective",
+                "malva-set".into(),
+                diagnostics,
+            ),
+            options_reset_comment_directive: get_value(
+                &mut config,
+                "optionsResetCommentDirective",
+                "malva-reset".into(),
+                diagnostics,
             ),
             ignore_comment_directive: get_value(
                 &mut config,
                 "ignoreCommentDirective",
                 "malva-ignore".into(),
-                &mut diagnostics,
+                diagnostics,
+            ),
+            ignore_start_comment_directive: get_value(
+                &mut config,
+                "ignoreStartCommentDirective",
+                "malva-ignore-start".into(),
+                diagnostics,
+            ),
+            ignore_end_comment_directive: get_value(
+                &mut config,
+                "ignoreEndCommentDirective",
+                "malva-ignore-end".into(),
+                diagnostics,
             ),
             ignore_file_comment_directive: get_value(
                 &mut config,
                 "ignoreFileCommentDirective",
                 "dprint-ignore-file".into(),
-                &mut diagnostics,
+                diagnostics,
+            ),
+            config_comment_directive: get_value(
+                &mut config,
+                "configCommentDirective",
+                "malva-config".into(),
+                diagnostics,
             ),
         },
+        verify: get_value(&mut config, "verify", false, diagnostics),
+        ..Default::default()
     };
+    if malva_config.verify && !cfg!(feature = "verify") {
+        diagnostics.push(ConfigurationDiagnostic {
+            property_name: "verify".into(),
+            message: "`verify` requires the `verify` feature of this plugin".into(),
+        });
+    }
 
     diagnostics.extend(get_unknown_property_diagnostics(config));
 
-    PluginResolveConfigurationResult {
-        config: malva_config,
-        diagnostics,
-        file_matching: FileMatchingInfo {
-            file_extensions: vec!["css".into(), "scss".into(), "sass".into(), "less".into()],
-            file_names: vec![],
+    malva_config
+}
+
+fn resolve_declaration_order(
+    config: &mut ConfigKeyMap,
+    diagnostics: &mut Vec<ConfigurationDiagnostic>,
+) -> Option<DeclarationOrder> {
+    let order = match config.shift_remove("declarationOrder") {
+        Some(ConfigKeyValue::String(value)) => match &*value {
+            "alphabetical" => Some(DeclarationOrder::Alphabetical),
+            "smacss" => Some(DeclarationOrder::Smacss),
+            "concentric" => Some(DeclarationOrder::Concentric),
+            _ => None,
         },
+        Some(ConfigKeyValue::Object(mut value)) => {
+            let order = match value.shift_remove("order") {
+                Some(ConfigKeyValue::Array(order)) => order
+                    .into_iter()
+                    .map(|property| match property {
+                        ConfigKeyValue::String(property) => Some(property),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>(),
+                _ => None,
+            };
+            let unspecified = match value.shift_remove("unspecified") {
+                Some(ConfigKeyValue::String(value)) => match &*value {
+                    "first" => Some(UnspecifiedProperties::First),
+                    "last" => Some(UnspecifiedProperties::Last),
+                    "alphabetical" => Some(UnspecifiedProperties::Alphabetical),
+                    _ => None,
+                },
+                None => Some(UnspecifiedProperties::default()),
+                _ => None,
+            };
+            order
+                .zip(unspecified)
+                .filter(|_| value.is_empty())
+                .map(|(order, unspecified)| DeclarationOrder::Custom { order, unspecified })
+        }
+        Some(ConfigKeyValue::Null) | None => return None,
+        _ => None,
+    };
+    if order.is_none() {
+        diagnostics.push(ConfigurationDiagnostic {
+            property_name: "declarationOrder".into(),
+            message: "invalid value for config `declarationOrder`".into(),
+        });
     }
+    order
 }
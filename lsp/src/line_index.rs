@@ -0,0 +1,38 @@
+use lsp_types::Position;
+use malva::{ColumnUnit, LineBounds};
+
+/// Convert between byte offsets and LSP positions whose characters are counted in UTF-16 code units.
+pub struct LineIndex<'s> {
+    text: &'s str,
+    line_bounds: LineBounds,
+}
+
+impl<'s> LineIndex<'s> {
+    pub fn new(text: &'s str) -> Self {
+        Self {
+            text,
+            line_bounds: LineBounds::new(text),
+        }
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        // LSP positions are 0-based
+        let (line, col) = self
+            .line_bounds
+            .line_col(self.text, offset, ColumnUnit::Utf16);
+        Position {
+            line: (line - 1) as u32,
+            character: (col - 1) as u32,
+        }
+    }
+
+    /// Positions beyond the end of line or the end of text are clamped.
+    pub fn offset(&self, position: Position) -> usize {
+        self.line_bounds.offset(
+            self.text,
+            position.line as usize + 1,
+            position.character as usize + 1,
+            ColumnUnit::Utf16,
+        )
+    }
+}
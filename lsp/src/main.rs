@@ -0,0 +1,285 @@
+use crate::line_index::LineIndex;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DocumentFormattingParams, DocumentOnTypeFormattingOptions, DocumentOnTypeFormattingParams,
+    DocumentRangeFormattingParams, FormattingOptions, OneOf, Position, PositionEncodingKind, Range,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Uri,
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as LspNotification,
+    },
+    request::{Formatting, OnTypeFormatting, RangeFormatting, Request as LspRequest},
+};
+use malva::{
+    Syntax,
+    config::{FormatOptions, PartialFormatOptions},
+    config_file::resolve_options_with_fallback,
+    detect_syntax, format_range, format_text_edits,
+};
+use std::{collections::HashMap, path::PathBuf, process::ExitCode};
+
+mod line_index;
+
+fn main() -> ExitCode {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        position_encoding: Some(PositionEncodingKind::UTF16),
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(
+            TextDocumentSyncKind::INCREMENTAL,
+        )),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        document_range_formatting_provider: Some(OneOf::Left(true)),
+        document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+            first_trigger_character: ";".into(),
+            more_trigger_character: Some(vec!["}".into()]),
+        }),
+        ..Default::default()
+    };
+    let result = connection
+        .initialize(serde_json::to_value(capabilities).unwrap_or_default())
+        .map_err(|error| error.to_string())
+        .and_then(|_| {
+            let mut server = Server::default();
+            for message in &connection.receiver {
+                match message {
+                    Message::Request(request) => {
+                        if connection
+                            .handle_shutdown(&request)
+                            .map_err(|error| error.to_string())?
+                        {
+                            break;
+                        }
+                        let response = server.handle_request(request);
+                        connection
+                            .sender
+                            .send(response.into())
+                            .map_err(|error| error.to_string())?;
+                    }
+                    Message::Notification(notification) => {
+                        server.handle_notification(notification);
+                    }
+                    Message::Response(_) => {}
+                }
+            }
+            Ok(())
+        });
+    drop(connection);
+
+    if let Err(error) = result.and_then(|_| io_threads.join().map_err(|error| error.to_string())) {
+        eprintln!("{error}");
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Uri, Document>,
+}
+
+struct Document {
+    text: String,
+    language_id: String,
+}
+
+enum Target {
+    Document,
+    /// Statements that intersect with the range.
+    Range(Range),
+    /// Position after the typed trigger character.
+    OnType(Position),
+}
+
+impl Server {
+    fn handle_request(&mut self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match &*request.method {
+            Formatting::METHOD => request
+                .extract::<DocumentFormattingParams>(Formatting::METHOD)
+                .map_err(|error| error.to_string())
+                .and_then(|(_, params)| {
+                    self.format(&params.text_document.uri, &params.options, Target::Document)
+                }),
+            RangeFormatting::METHOD => request
+                .extract::<DocumentRangeFormattingParams>(RangeFormatting::METHOD)
+                .map_err(|error| error.to_string())
+                .and_then(|(_, params)| {
+                    self.format(
+                        &params.text_document.uri,
+                        &params.options,
+                        Target::Range(params.range),
+                    )
+                }),
+            OnTypeFormatting::METHOD => request
+                .extract::<DocumentOnTypeFormattingParams>(OnTypeFormatting::METHOD)
+                .map_err(|error| error.to_string())
+                .map(|(_, params)| {
+                    let uri = &params.text_document_position.text_document.uri;
+                    let position = params.text_document_position.position;
+                    // code is usually incomplete while typing, so errors are ignored
+                    self.format(uri, &params.options, Target::OnType(position))
+                        .unwrap_or_default()
+                }),
+            _ => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("unsupported method: {}", request.method),
+                );
+            }
+        };
+        match result {
+            Ok(edits) => Response::new_ok(id, edits),
+            Err(message) => Response::new_err(id, ErrorCode::RequestFailed as i32, message),
+        }
+    }
+
+    fn handle_notification(&mut self, notification: Notification) {
+        match &*notification.method {
+            DidOpenTextDocument::METHOD => {
+                if let Ok(params) =
+                    notification.extract::<DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD)
+                {
+                    self.documents.insert(
+                        params.text_document.uri,
+                        Document {
+                            text: params.text_document.text,
+                            language_id: params.text_document.language_id,
+                        },
+                    );
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                if let Ok(params) = notification
+                    .extract::<DidChangeTextDocumentParams>(DidChangeTextDocument::METHOD)
+                    && let Some(document) = self.documents.get_mut(&params.text_document.uri)
+                {
+                    for change in params.content_changes {
+                        if let Some(range) = change.range {
+                            let line_index = LineIndex::new(&document.text);
+                            let start = line_index.offset(range.start);
+                            let end = line_index.offset(range.end);
+                            document.text.replace_range(start..end, &change.text);
+                        } else {
+                            document.text = change.text;
+                        }
+                    }
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                if let Ok(params) =
+                    notification.extract::<DidCloseTextDocumentParams>(DidCloseTextDocument::METHOD)
+                {
+                    self.documents.remove(&params.text_document.uri);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Format the document, and return edits with UTF-16 positions.
+    fn format(
+        &self,
+        uri: &Uri,
+        formatting_options: &FormattingOptions,
+        target: Target,
+    ) -> Result<Vec<TextEdit>, String> {
+        let document = self
+            .documents
+            .get(uri)
+            .ok_or_else(|| format!("document '{}' isn't opened", uri.as_str()))?;
+        let path = to_file_path(uri);
+        let syntax = match &*document.language_id {
+            "css" => Syntax::Css,
+            "scss" => Syntax::Scss,
+            "sass" => Syntax::Sass,
+            "less" => Syntax::Less,
+            _ => path
+                .as_deref()
+                .and_then(detect_syntax)
+                .unwrap_or(Syntax::Css),
+        };
+        // options from editor have the lowest precedence,
+        // so config files and `.editorconfig` files override them
+        let fallback = PartialFormatOptions {
+            use_tabs: Some(!formatting_options.insert_spaces),
+            indent_width: Some(formatting_options.tab_size as usize),
+            ..Default::default()
+        };
+        let options = match &path {
+            Some(path) => {
+                resolve_options_with_fallback(path, &fallback).map_err(|error| error.to_string())?
+            }
+            None => {
+                let mut options = FormatOptions::default();
+                fallback.apply_to(&mut options);
+                options
+            }
+        };
+
+        let text = &document.text;
+        let line_index = LineIndex::new(text);
+        let edits = match target {
+            Target::Document => {
+                format_text_edits(text, syntax, &options).map_err(|error| error.to_string())?
+            }
+            Target::Range(range) => {
+                let range = line_index.offset(range.start)..line_index.offset(range.end);
+                let output = format_range(text, syntax, &options, range)
+                    .map_err(|error| error.to_string())?;
+                malva::TextEdit::diff(text, &output).into_iter().collect()
+            }
+            Target::OnType(position) => {
+                // Cursor is after the trigger character.
+                // Semicolon isn't a part of statement, so the statement before it is formatted;
+                // while for `}`, the whole rule is formatted.
+                let offset = line_index.offset(position);
+                let end = text[..offset]
+                    .strip_suffix(';')
+                    .map_or(offset, |text| text.trim_end().len());
+                let output = format_range(text, syntax, &options, end.saturating_sub(1)..end)
+                    .map_err(|error| error.to_string())?;
+                malva::TextEdit::diff(text, &output).into_iter().collect()
+            }
+        };
+        Ok(edits
+            .into_iter()
+            .map(|edit| TextEdit {
+                range: Range::new(
+                    line_index.position(edit.range.start),
+                    line_index.position(edit.range.end),
+                ),
+                new_text: edit.new_text,
+            })
+            .collect())
+    }
+}
+
+fn to_file_path(uri: &Uri) -> Option<PathBuf> {
+    let path = uri.as_str().strip_prefix("file://")?;
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = path
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    let path = String::from_utf8(decoded).ok()?;
+    // Windows paths look like `/C:/path/to/file`
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if cfg!(windows) && drive.is_ascii_alphabetic() => &path[1..],
+        _ => &path,
+    };
+    Some(PathBuf::from(path))
+}
@@ -0,0 +1,144 @@
+use serde_json::{Value, json};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// Send messages to language server, then collect responses keyed by request ID.
+fn run(messages: &[Value]) -> Vec<Value> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_malva-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    for message in messages {
+        let content = message.to_string();
+        write!(stdin, "Content-Length: {}\r\n\r\n{content}", content.len()).unwrap();
+    }
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let mut stdout = &*String::from_utf8(output.stdout).unwrap();
+    let mut responses = vec![];
+    while let Some((header, rest)) = stdout.split_once("\r\n\r\n") {
+        let length = header
+            .strip_prefix("Content-Length: ")
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        responses.push(serde_json::from_str(&rest[..length]).unwrap());
+        stdout = &rest[length..];
+    }
+    responses
+}
+
+fn request(id: u32, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+#[test]
+fn formatting() {
+    let uri = "untitled:a.css";
+    let options = json!({ "tabSize": 4, "insertSpaces": true });
+    let responses = run(&[
+        request(1, "initialize", json!({ "capabilities": {} })),
+        notification("initialized", json!({})),
+        notification(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {
+                    "uri": uri,
+                    "languageId": "css",
+                    "version": 1,
+                    "text": "a::before{content:\"😀\";color:red}\nb{color:red}\n",
+                }
+            }),
+        ),
+        request(
+            2,
+            "textDocument/formatting",
+            json!({ "textDocument": { "uri": uri }, "options": options }),
+        ),
+        notification(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": uri, "version": 2 },
+                "contentChanges": [{
+                    "range": {
+                        "start": { "line": 0, "character": 32 },
+                        "end": { "line": 0, "character": 32 },
+                    },
+                    "text": ";",
+                }],
+            }),
+        ),
+        request(
+            3,
+            "textDocument/rangeFormatting",
+            json!({
+                "textDocument": { "uri": uri },
+                "range": {
+                    "start": { "line": 1, "character": 0 },
+                    "end": { "line": 1, "character": 1 },
+                },
+                "options": options,
+            }),
+        ),
+        request(
+            4,
+            "textDocument/onTypeFormatting",
+            json!({
+                "textDocument": { "uri": uri },
+                "position": { "line": 0, "character": 33 },
+                "ch": ";",
+                "options": options,
+            }),
+        ),
+        request(5, "shutdown", Value::Null),
+        notification("exit", Value::Null),
+    ]);
+
+    assert_eq!(
+        responses[0]["result"]["capabilities"]["documentFormattingProvider"],
+        true
+    );
+    let edits = &responses[1]["result"];
+    assert_eq!(edits.as_array().unwrap().len(), 10);
+    // character after emoji is counted in UTF-16 code units
+    assert_eq!(
+        edits[3],
+        json!({
+            "range": {
+                "start": { "line": 0, "character": 23 },
+                "end": { "line": 0, "character": 23 },
+            },
+            "newText": "\n    ",
+        })
+    );
+    assert_eq!(
+        responses[2]["result"],
+        json!([{
+            "range": {
+                "start": { "line": 1, "character": 1 },
+                "end": { "line": 1, "character": 11 },
+            },
+            "newText": " {\n    color: red;\n",
+        }])
+    );
+    assert_eq!(
+        responses[3]["result"],
+        json!([{
+            "range": {
+                "start": { "line": 0, "character": 29 },
+                "end": { "line": 0, "character": 29 },
+            },
+            "newText": " ",
+        }])
+    );
+}
@@ -0,0 +1,68 @@
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use malva::{Syntax, config::FormatOptions, format_text};
+use std::{fmt::Write, hint::black_box};
+
+/// Generate stylesheet like icon fonts, which are usually large and generated by tools.
+fn icon_font(rules: usize) -> String {
+    let mut input = String::with_capacity(rules * 64);
+    for i in 0..rules {
+        let _ = writeln!(
+            input,
+            ".icon-{i}:before {{\n  content: \"\\e{:03x}\";\n}}\n",
+            i % 0x1000
+        );
+    }
+    input
+}
+
+/// Generate design tokens with comments for every declaration.
+fn design_tokens(rules: usize) -> String {
+    let mut input = String::with_capacity(rules * 256);
+    for i in 0..rules {
+        let _ = writeln!(
+            input,
+            "/* Tokens of theme {i} */\n.theme-{i} {{\n  /* primary color */\n  --color-primary: #{:06x}; /* from palette */\n  /* spacing */\n  --spacing: {}px; /* base unit */\n}}\n",
+            i * 0x10 % 0x1000000,
+            i % 64
+        );
+    }
+    input
+}
+
+/// Formatting time should grow linearly with input size,
+/// so throughput is expected to be the same for all sizes.
+fn large_input(c: &mut Criterion) {
+    let options = FormatOptions::default();
+    let mut group = c.benchmark_group("large input");
+    group.sample_size(10);
+    for rules in [1_000, 5_000, 12_500] {
+        let input = icon_font(rules);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{} lines", input.lines().count())),
+            &input,
+            |b, input| b.iter(|| format_text(black_box(input), Syntax::Css, &options)),
+        );
+    }
+    group.finish();
+}
+
+/// Looking up comments shouldn't scan all comments for every node.
+fn comment_heavy(c: &mut Criterion) {
+    let options = FormatOptions::default();
+    let mut group = c.benchmark_group("comment heavy");
+    group.sample_size(10);
+    for rules in [500, 2_500, 6_250] {
+        let input = design_tokens(rules);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{} comments", rules * 5)),
+            &input,
+            |b, input| b.iter(|| format_text(black_box(input), Syntax::Css, &options)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, large_input, comment_heavy);
+criterion_main!(benches);
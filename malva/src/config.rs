@@ -1,7 +1,9 @@
 //! Types about configuration.
 
+use raffia::Syntax;
 #[cfg(feature = "config_serde")]
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 #[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
@@ -14,6 +16,45 @@ pub struct FormatOptions {
     pub layout: LayoutOptions,
     #[cfg_attr(feature = "config_serde", serde(flatten))]
     pub language: LanguageOptions,
+
+    /// Options that only apply to CSS.
+    pub css: Option<PartialFormatOptions>,
+    /// Options that only apply to SCSS.
+    pub scss: Option<PartialFormatOptions>,
+    /// Options that only apply to indented Sass.
+    pub sass: Option<PartialFormatOptions>,
+    /// Options that only apply to Less.
+    pub less: Option<PartialFormatOptions>,
+
+    /// Check that formatted code has the same syntax tree as the original code,
+    /// and formatting it again won't change anything.
+    /// If the check fails, [`Error::UnstableOutput`](crate::Error::UnstableOutput) will be returned.
+    /// Comments that aren't printed are reported as [`Error::LostComment`](crate::Error::LostComment).
+    ///
+    /// This makes formatting slower, so it's disabled by default.
+    /// It requires the `verify` feature, otherwise [`Error::InvalidOptions`](crate::Error::InvalidOptions)
+    /// will be returned when it's enabled.
+    pub verify: bool,
+}
+
+impl FormatOptions {
+    /// Resolve options for specific syntax by applying its overrides.
+    /// Options will be borrowed if there're no overrides for that syntax.
+    pub fn resolve(&self, syntax: Syntax) -> Cow<'_, FormatOptions> {
+        let overrides = match syntax {
+            Syntax::Css => &self.css,
+            Syntax::Scss => &self.scss,
+            Syntax::Sass => &self.sass,
+            Syntax::Less => &self.less,
+        };
+        if let Some(overrides) = overrides {
+            let mut options = self.clone();
+            overrides.apply_to(&mut options);
+            Cow::Owned(options)
+        } else {
+            Cow::Borrowed(self)
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -136,6 +177,13 @@ pub struct LanguageOptions {
     /// See [`declarationOrderGroupBy`](https://malva.netlify.app/config/declaration-order-group-by.html)
     pub declaration_order_group_by: DeclarationOrderGroupBy,
 
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "declarationOrderSeparateGroups")
+    )]
+    /// See [`declarationOrderSeparateGroups`](https://malva.netlify.app/config/declaration-order-separate-groups.html)
+    pub declaration_order_separate_groups: bool,
+
     #[cfg_attr(feature = "config_serde", serde(alias = "singleLineBlockThreshold"))]
     /// See [`singleLineBlockThreshold`](https://malva.netlify.app/config/single-line-block-threshold.html)
     pub single_line_block_threshold: Option<usize>,
@@ -264,13 +312,39 @@ pub struct LanguageOptions {
     /// See [`selectorOverrideCommentDirective`](https://malva.netlify.app/config/selector-override-comment-directive.html)
     pub selector_override_comment_directive: String,
 
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "optionsOverrideCommentDirective")
+    )]
+    /// See [`optionsOverrideCommentDirective`](https://malva.netlify.app/config/options-override-comment-directive.html)
+    pub options_override_comment_directive: String,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "optionsResetCommentDirective")
+    )]
+    /// See [`optionsResetCommentDirective`](https://malva.netlify.app/config/options-reset-comment-directive.html)
+    pub options_reset_comment_directive: String,
+
     #[cfg_attr(feature = "config_serde", serde(alias = "ignoreCommentDirective"))]
     /// See [`ignoreCommentDirective`](https://malva.netlify.app/config/ignore-comment-directive.html)
     pub ignore_comment_directive: String,
 
+    #[cfg_attr(feature = "config_serde", serde(alias = "ignoreStartCommentDirective"))]
+    /// See [`ignoreStartCommentDirective`](https://malva.netlify.app/config/ignore-start-comment-directive.html)
+    pub ignore_start_comment_directive: String,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "ignoreEndCommentDirective"))]
+    /// See [`ignoreEndCommentDirective`](https://malva.netlify.app/config/ignore-end-comment-directive.html)
+    pub ignore_end_comment_directive: String,
+
     #[cfg_attr(feature = "config_serde", serde(alias = "ignoreFileCommentDirective"))]
     /// See [`ignoreFileCommentDirective`](https://malva.netlify.app/config/ignore-file-comment-directive.html)
     pub ignore_file_comment_directive: String,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "configCommentDirective"))]
+    /// See [`configCommentDirective`](https://malva.netlify.app/config/config-comment-directive.html)
+    pub config_comment_directive: String,
 }
 
 impl Default for LanguageOptions {
@@ -289,6 +363,7 @@ impl Default for LanguageOptions {
             linebreak_in_pseudo_parens: false,
             declaration_order: None,
             declaration_order_group_by: DeclarationOrderGroupBy::default(),
+            declaration_order_separate_groups: false,
             single_line_block_threshold: None,
             keyframe_selector_notation: None,
             attr_value_quotes: AttrValueQuotes::default(),
@@ -307,8 +382,406 @@ impl Default for LanguageOptions {
             font_family_names: FontFamilyNames::default(),
             nth_plus_spacing: false,
             selector_override_comment_directive: "malva-selector-override".into(),
+            options_override_comment_directive: "malva-set".into(),
+            options_reset_comment_directive: "malva-reset".into(),
             ignore_comment_directive: "malva-ignore".into(),
+            ignore_start_comment_directive: "malva-ignore-start".into(),
+            ignore_end_comment_directive: "malva-ignore-end".into(),
             ignore_file_comment_directive: "malva-ignore-file".into(),
+            config_comment_directive: "malva-config".into(),
+        }
+    }
+}
+
+/// Generate [`PartialFormatOptions`] with its `apply_to` method and
+/// conversion from [`FormatOptions`], so each field is only listed once.
+///
+/// Fields marked with `as Option` are also optional in [`FormatOptions`].
+macro_rules! partial_format_options {
+    ($(
+        $(#[$attr:meta])*
+        $group:ident.$field:ident: $ty:ty $(as $option:ident)?,
+    )*) => {
+        #[derive(Clone, Debug, Default)]
+        #[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+        #[cfg_attr(feature = "config_serde", serde(default))]
+        /// Options that partially override [`FormatOptions`].
+        /// Fields with `None` value won't override.
+        pub struct PartialFormatOptions {
+            $(
+                $(#[$attr])*
+                pub $field: Option<$ty>,
+            )*
+        }
+
+        impl PartialFormatOptions {
+            /// Override options with fields that aren't `None`.
+            pub fn apply_to(&self, options: &mut FormatOptions) {
+                $(
+                    if let Some(value) = &self.$field {
+                        options.$group.$field = full_value!(value.clone() $(, $option)?);
+                    }
+                )*
+            }
+        }
+
+        impl From<FormatOptions> for PartialFormatOptions {
+            fn from(options: FormatOptions) -> Self {
+                Self {
+                    $(
+                        $field: partial_value!(options.$group.$field $(, $option)?),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+macro_rules! full_value {
+    ($value:expr) => {
+        $value
+    };
+    ($value:expr, Option) => {
+        Some($value)
+    };
+}
+
+macro_rules! partial_value {
+    ($value:expr) => {
+        Some($value)
+    };
+    ($value:expr, Option) => {
+        $value
+    };
+}
+
+partial_format_options! {
+    #[cfg_attr(feature = "config_serde", serde(alias = "printWidth"))]
+    /// See [`printWidth`](https://malva.netlify.app/config/print-width.html)
+    layout.print_width: usize,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "useTabs"))]
+    /// See [`useTabs`](https://malva.netlify.app/config/use-tabs.html)
+    layout.use_tabs: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "indentWidth"))]
+    /// See [`indentWidth`](https://malva.netlify.app/config/indent-width.html)
+    layout.indent_width: usize,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "lineBreak", alias = "linebreak")
+    )]
+    /// See [`lineBreak`](https://malva.netlify.app/config/line-break.html)
+    layout.line_break: LineBreak,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "hexCase"))]
+    /// See [`hexCase`](https://malva.netlify.app/config/hex-case.html)
+    language.hex_case: HexCase,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "hexColorLength"))]
+    /// See [`hexColorLength`](https://malva.netlify.app/config/hex-color-length.html)
+    language.hex_color_length: HexColorLength as Option,
+
+    /// See [`quotes`](https://malva.netlify.app/config/quotes.html)
+    language.quotes: Quotes,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(rename = "attr_selector.quotes", alias = "attrSelector.quotes")
+    )]
+    /// See [`quotes`](https://malva.netlify.app/config/quotes.html)
+    language.attr_selector_quotes: Quotes as Option,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "operatorLinebreak", alias = "operatorLineBreak")
+    )]
+    /// See [`operatorLinebreak`](https://malva.netlify.app/config/operator-linebreak.html)
+    language.operator_linebreak: OperatorLineBreak,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "blockSelectorLinebreak", alias = "blockSelectorLineBreak")
+    )]
+    /// See [`blockSelectorLinebreak`](https://malva.netlify.app/config/block-selector-linebreak.html)
+    language.block_selector_linebreak: BlockSelectorLineBreak,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "omitNumberLeadingZero"))]
+    /// See [`omitNumberLeadingZero`](https://malva.netlify.app/config/omit-number-leading-zero.html)
+    language.omit_number_leading_zero: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "trailingComma"))]
+    /// See [`trailingComma`](https://malva.netlify.app/config/trailing-comma.html)
+    language.trailing_comma: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "formatComments"))]
+    /// See [`formatComments`](https://malva.netlify.app/config/format-comments.html)
+    language.format_comments: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "alignComments"))]
+    language.align_comments: bool,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "linebreakInPseudoParens", alias = "lineBreakInPseudoParens")
+    )]
+    /// See [`linebreakInPseudoParens`](https://malva.netlify.app/config/linebreak-in-pseudo-parens.html)
+    language.linebreak_in_pseudo_parens: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "declarationOrder"))]
+    /// See [`declarationOrder`](https://malva.netlify.app/config/declaration-order.html)
+    language.declaration_order: DeclarationOrder as Option,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "declarationOrderGroupBy"))]
+    /// See [`declarationOrderGroupBy`](https://malva.netlify.app/config/declaration-order-group-by.html)
+    language.declaration_order_group_by: DeclarationOrderGroupBy,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "declarationOrderSeparateGroups")
+    )]
+    /// See [`declarationOrderSeparateGroups`](https://malva.netlify.app/config/declaration-order-separate-groups.html)
+    language.declaration_order_separate_groups: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "singleLineBlockThreshold"))]
+    /// See [`singleLineBlockThreshold`](https://malva.netlify.app/config/single-line-block-threshold.html)
+    language.single_line_block_threshold: usize as Option,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "keyframeSelectorNotation"))]
+    /// See [`keyframeSelectorNotation`](https://malva.netlify.app/config/keyframe-selector-notation.html)
+    language.keyframe_selector_notation: KeyframeSelectorNotation as Option,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "attrValueQuotes"))]
+    /// See [`attrValueQuotes`](https://malva.netlify.app/config/attr-value-quotes.html)
+    language.attr_value_quotes: AttrValueQuotes,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "preferSingleLine"))]
+    /// See [`preferSingleLine`](https://malva.netlify.app/config/prefer-single-line.html)
+    language.prefer_single_line: bool,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            rename = "selectors.prefer_single_line",
+            alias = "selectors.preferSingleLine"
+        )
+    )]
+    /// See [`preferSingleLine`](https://malva.netlify.app/config/prefer-single-line.html)
+    language.selectors_prefer_single_line: bool as Option,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            rename = "function_args.prefer_single_line",
+            alias = "functionArgs.preferSingleLine"
+        )
+    )]
+    /// See [`preferSingleLine`](https://malva.netlify.app/config/prefer-single-line.html)
+    language.function_args_prefer_single_line: bool as Option,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            rename = "sass_content_at_rule.prefer_single_line",
+            alias = "sassContentAtRule.preferSingleLine"
+        )
+    )]
+    /// See [`preferSingleLine`](https://malva.netlify.app/config/prefer-single-line.html)
+    language.sass_content_at_rule_prefer_single_line: bool as Option,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            rename = "sass_include_at_rule.prefer_single_line",
+            alias = "sassIncludeAtRule.preferSingleLine"
+        )
+    )]
+    /// See [`preferSingleLine`](https://malva.netlify.app/config/prefer-single-line.html)
+    language.sass_include_at_rule_prefer_single_line: bool as Option,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            rename = "sass_map.prefer_single_line",
+            alias = "sassMap.preferSingleLine"
+        )
+    )]
+    /// See [`preferSingleLine`](https://malva.netlify.app/config/prefer-single-line.html)
+    language.sass_map_prefer_single_line: bool as Option,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            rename = "sass_module_config.prefer_single_line",
+            alias = "sassModuleConfig.preferSingleLine"
+        )
+    )]
+    /// See [`preferSingleLine`](https://malva.netlify.app/config/prefer-single-line.html)
+    language.sass_module_config_prefer_single_line: bool as Option,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            rename = "sass_params.prefer_single_line",
+            alias = "sassParams.preferSingleLine"
+        )
+    )]
+    /// See [`preferSingleLine`](https://malva.netlify.app/config/prefer-single-line.html)
+    language.sass_params_prefer_single_line: bool as Option,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            rename = "less_import_options.prefer_single_line",
+            alias = "lessImportOptions.preferSingleLine"
+        )
+    )]
+    /// See [`preferSingleLine`](https://malva.netlify.app/config/prefer-single-line.html)
+    language.less_import_options_prefer_single_line: bool as Option,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            rename = "less_mixin_args.prefer_single_line",
+            alias = "lessMixinArgs.preferSingleLine"
+        )
+    )]
+    /// See [`preferSingleLine`](https://malva.netlify.app/config/prefer-single-line.html)
+    language.less_mixin_args_prefer_single_line: bool as Option,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            rename = "less_mixin_params.prefer_single_line",
+            alias = "lessMixinParams.preferSingleLine"
+        )
+    )]
+    /// See [`preferSingleLine`](https://malva.netlify.app/config/prefer-single-line.html)
+    language.less_mixin_params_prefer_single_line: bool as Option,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "singleLineTopLevelDeclarations")
+    )]
+    /// See [`singleLineTopLevelDeclarations`](https://malva.netlify.app/config/single-line-top-level-declarations.html)
+    language.single_line_top_level_declarations: bool,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "fontFamilyNames"))]
+    /// See [`fontFamilyNames`](https://malva.netlify.app/config/font-family-names.html)
+    language.font_family_names: FontFamilyNames,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "nthPlusSpacing"))]
+    /// See [`nthPlusSpacing`](https://malva.netlify.app/config/nth-plus-spacing.html)
+    language.nth_plus_spacing: bool,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "selectorOverrideCommentDirective")
+    )]
+    /// See [`selectorOverrideCommentDirective`](https://malva.netlify.app/config/selector-override-comment-directive.html)
+    language.selector_override_comment_directive: String,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "optionsOverrideCommentDirective")
+    )]
+    /// See [`optionsOverrideCommentDirective`](https://malva.netlify.app/config/options-override-comment-directive.html)
+    language.options_override_comment_directive: String,
+
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(alias = "optionsResetCommentDirective")
+    )]
+    /// See [`optionsResetCommentDirective`](https://malva.netlify.app/config/options-reset-comment-directive.html)
+    language.options_reset_comment_directive: String,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "ignoreCommentDirective"))]
+    /// See [`ignoreCommentDirective`](https://malva.netlify.app/config/ignore-comment-directive.html)
+    language.ignore_comment_directive: String,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "ignoreStartCommentDirective"))]
+    /// See [`ignoreStartCommentDirective`](https://malva.netlify.app/config/ignore-start-comment-directive.html)
+    language.ignore_start_comment_directive: String,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "ignoreEndCommentDirective"))]
+    /// See [`ignoreEndCommentDirective`](https://malva.netlify.app/config/ignore-end-comment-directive.html)
+    language.ignore_end_comment_directive: String,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "ignoreFileCommentDirective"))]
+    /// See [`ignoreFileCommentDirective`](https://malva.netlify.app/config/ignore-file-comment-directive.html)
+    language.ignore_file_comment_directive: String,
+
+    #[cfg_attr(feature = "config_serde", serde(alias = "configCommentDirective"))]
+    /// See [`configCommentDirective`](https://malva.netlify.app/config/config-comment-directive.html)
+    language.config_comment_directive: String,
+}
+
+/// Error message when options in comments can't be parsed without the `config_serde` feature.
+#[cfg(not(feature = "config_serde"))]
+const CONFIG_SERDE_REQUIRED: &str =
+    "overriding options requires the `config_serde` feature of Malva";
+
+impl PartialFormatOptions {
+    /// Parse options from `key=value` pairs that are separated by whitespace,
+    /// such as `printWidth=120 quotes=alwaysSingle`.
+    /// Values are parsed as JSON if possible, otherwise they're treated as strings.
+    ///
+    /// Parsing requires the `config_serde` feature, otherwise an error is always returned.
+    pub(crate) fn from_directive(pairs: &str) -> Result<Self, String> {
+        #[cfg(feature = "config_serde")]
+        {
+            use serde_json::{Map, Value};
+            let options = pairs
+                .split_ascii_whitespace()
+                .map(|pair| {
+                    let (key, value) = pair
+                        .split_once('=')
+                        .ok_or_else(|| format!("expected `key=value`, but found `{pair}`"))?;
+                    let value = serde_json::from_str(value)
+                        .unwrap_or_else(|_| Value::String(value.to_owned()));
+                    Ok((key.to_owned(), value))
+                })
+                .collect::<Result<Map<_, _>, String>>()?;
+            let partial = serde_json::from_value(Value::Object(options.clone()))
+                .map_err(|error| error.to_string())?;
+            // unknown keys are ignored by deserializer,
+            // so each key is checked by seeing if it fills any field alone
+            for (key, value) in options {
+                let fields = serde_json::from_value::<Self>(Value::Object(Map::from_iter([(
+                    key.clone(),
+                    value,
+                )])))
+                .and_then(serde_json::to_value)
+                .map_err(|error| error.to_string())?;
+                if fields
+                    .as_object()
+                    .is_some_and(|fields| fields.values().all(Value::is_null))
+                {
+                    return Err(format!("unknown option `{key}`"));
+                }
+            }
+            Ok(partial)
+        }
+        #[cfg(not(feature = "config_serde"))]
+        {
+            let _ = pairs;
+            Err(CONFIG_SERDE_REQUIRED.into())
+        }
+    }
+
+    /// Parse options from JSON object, such as `{ "printWidth": 120 }`.
+    ///
+    /// Parsing requires the `config_serde` feature, otherwise an error is always returned.
+    pub(crate) fn from_json(json: &str) -> Result<Self, String> {
+        #[cfg(feature = "config_serde")]
+        {
+            serde_json::from_str(json).map_err(|error| error.to_string())
+        }
+        #[cfg(not(feature = "config_serde"))]
+        {
+            let _ = json;
+            Err(CONFIG_SERDE_REQUIRED.into())
         }
     }
 }
@@ -389,6 +862,28 @@ pub enum DeclarationOrder {
     Smacss,
     /// Order properties applying outside the box model, moving inward to intrinsic changes.
     Concentric,
+    /// Order properties by the given list.
+    /// Entries that end with `*` match properties with that prefix, such as `margin-*`.
+    #[cfg_attr(feature = "config_serde", serde(untagged))]
+    Custom {
+        order: Vec<String>,
+        #[cfg_attr(feature = "config_serde", serde(default))]
+        unspecified: UnspecifiedProperties,
+    },
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "config_serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "config_serde", serde(rename_all = "kebab-case"))]
+/// Where to place properties that aren't in the list of [`DeclarationOrder::Custom`].
+pub enum UnspecifiedProperties {
+    /// Place them before specified properties.
+    First,
+    /// Place them after specified properties.
+    #[default]
+    Last,
+    /// Place them after specified properties, and sort them alphabetically.
+    Alphabetical,
 }
 
 #[derive(Clone, Debug, Default)]
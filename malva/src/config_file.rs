@@ -0,0 +1,324 @@
+//! Discover and load configuration files.
+//!
+//! Config file can be `malva.toml` or `.malvarc.json`.
+//! Besides options of [`FormatOptions`], it can contain an `overrides` section
+//! which applies partial options to files that match glob patterns:
+//!
+//! ```toml
+//! printWidth = 80
+//!
+//! [[overrides]]
+//! files = ["legacy/**/*.less"]
+//! options = { printWidth = 120, quotes = "alwaysSingle" }
+//! ```
+//!
+//! Glob patterns are relative to the directory of config file.
+//! Patterns without slash match file name only.
+//!
+//! Config files in parent directories are merged as well,
+//! and options in nearer config files take precedence.
+//! Searching stops at the config file with `root = true`.
+//!
+//! When resolving options with [`resolve_options`], layout options that aren't
+//! specified in config file are taken from `.editorconfig` files.
+
+use crate::{
+    Error,
+    config::{FormatOptions, PartialFormatOptions},
+    editorconfig,
+};
+use globset::{GlobBuilder, GlobMatcher};
+use serde_json::{Map, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Names of config files. When there're multiple config files in a directory,
+/// the first one takes precedence.
+pub const CONFIG_FILE_NAMES: [&str; 2] = ["malva.toml", ".malvarc.json"];
+
+/// Loaded config file with overrides.
+pub struct ConfigFile {
+    dir: PathBuf,
+    root: bool,
+    base: Map<String, Value>,
+    overrides: Vec<Override>,
+}
+
+struct Override {
+    globs: Vec<GlobMatcher>,
+    options: Map<String, Value>,
+}
+
+impl ConfigFile {
+    /// Load config file from the given path.
+    /// File format is determined by its extension: `.json` for JSON, otherwise TOML.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = std::path::absolute(path.as_ref()).map_err(|error| {
+            Error::Io(format!(
+                "invalid path '{}': {error}",
+                path.as_ref().display()
+            ))
+        })?;
+        let content = fs::read_to_string(&path)
+            .map_err(|error| Error::Io(format!("failed to read '{}': {error}", path.display())))?;
+        let value = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str::<Value>(&content).map_err(|error| error.to_string())
+        } else {
+            toml::from_str::<Value>(&content).map_err(|error| error.to_string())
+        }
+        .map_err(|error| {
+            Error::InvalidOptions(format!("failed to parse '{}': {error}", path.display()))
+        })?;
+
+        let Value::Object(mut base) = value else {
+            return Err(Error::InvalidOptions(format!(
+                "config in '{}' must be an object",
+                path.display()
+            )));
+        };
+        let root = match base.remove("root") {
+            Some(Value::Bool(root)) => root,
+            None => false,
+            Some(_) => {
+                return Err(Error::InvalidOptions("`root` must be a boolean".into()));
+            }
+        };
+        let overrides = match base.remove("overrides") {
+            Some(Value::Array(overrides)) => overrides
+                .into_iter()
+                .map(parse_override)
+                .collect::<Result<Vec<_>, _>>()?,
+            None => vec![],
+            Some(_) => {
+                return Err(Error::InvalidOptions("`overrides` must be an array".into()));
+            }
+        };
+
+        Ok(Self {
+            dir: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            root,
+            base,
+            overrides,
+        })
+    }
+
+    /// Whether this config file has `root = true`,
+    /// which stops searching config files in parent directories.
+    pub fn is_root(&self) -> bool {
+        self.root
+    }
+
+    /// Resolve options for the given file by merging matched overrides over the base config.
+    /// Later overrides take precedence over earlier ones.
+    pub fn options_for(&self, file: impl AsRef<Path>) -> Result<FormatOptions, Error> {
+        serde_json::from_value(Value::Object(self.merge_options(file)))
+            .map_err(|error| Error::InvalidOptions(error.to_string()))
+    }
+
+    /// Resolve options like [`ConfigFile::options_for`],
+    /// but options that aren't specified in config file fall back to `fallback`.
+    pub fn options_with_fallback_for(
+        &self,
+        file: impl AsRef<Path>,
+        fallback: &PartialFormatOptions,
+    ) -> Result<FormatOptions, Error> {
+        options_with_fallbacks(self.merge_options(file), &[fallback])
+    }
+
+    fn merge_options(&self, file: impl AsRef<Path>) -> Map<String, Value> {
+        let file = file.as_ref();
+        let file = std::path::absolute(file).unwrap_or_else(|_| file.to_path_buf());
+        let relative = file.strip_prefix(&self.dir).unwrap_or(&file);
+
+        let mut options = self.base.clone();
+        self.overrides
+            .iter()
+            .filter(|item| item.globs.iter().any(|glob| glob.is_match(relative)))
+            .for_each(|item| merge(&mut options, item.options.clone()));
+        options
+    }
+}
+
+/// Fallbacks are applied in order, so later ones take precedence over earlier ones.
+fn options_with_fallbacks(
+    options: Map<String, Value>,
+    fallbacks: &[&PartialFormatOptions],
+) -> Result<FormatOptions, Error> {
+    let value = Value::Object(options);
+    let mut options = serde_json::from_value::<FormatOptions>(value.clone())
+        .map_err(|error| Error::InvalidOptions(error.to_string()))?;
+    let specified = serde_json::from_value::<PartialFormatOptions>(value)
+        .map_err(|error| Error::InvalidOptions(error.to_string()))?;
+    fallbacks
+        .iter()
+        .for_each(|fallback| fallback.apply_to(&mut options));
+    specified.apply_to(&mut options);
+    Ok(options)
+}
+
+/// Merge options from `source` into `target` recursively.
+///
+/// Keys that only differ in case or underscores (such as `printWidth` and `print_width`)
+/// are treated as the same key, so the one from `source` replaces the one in `target`.
+fn merge(target: &mut Map<String, Value>, source: Map<String, Value>) {
+    for (key, value) in source {
+        let existing = target
+            .keys()
+            .find(|existing| normalize_key(existing) == normalize_key(&key))
+            .cloned()
+            .and_then(|existing| target.remove(&existing));
+        let value = match (existing, value) {
+            (Some(Value::Object(mut existing)), Value::Object(value)) => {
+                merge(&mut existing, value);
+                Value::Object(existing)
+            }
+            (_, value) => value,
+        };
+        target.insert(key, value);
+    }
+}
+
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn parse_override(value: Value) -> Result<Override, Error> {
+    let Value::Object(mut item) = value else {
+        return Err(Error::InvalidOptions(
+            "item of `overrides` must be an object".into(),
+        ));
+    };
+    let patterns = match item.remove("files") {
+        Some(Value::String(pattern)) => vec![pattern],
+        Some(Value::Array(patterns)) => patterns
+            .into_iter()
+            .map(|pattern| match pattern {
+                Value::String(pattern) => Ok(pattern),
+                _ => Err(Error::InvalidOptions(
+                    "`files` of overrides must be a string or an array of strings".into(),
+                )),
+            })
+            .collect::<Result<_, _>>()?,
+        _ => {
+            return Err(Error::InvalidOptions(
+                "`files` of overrides must be a string or an array of strings".into(),
+            ));
+        }
+    };
+    let globs = patterns
+        .iter()
+        .map(|pattern| {
+            // patterns without slash match file name in any directory
+            let pattern = if pattern.contains('/') {
+                pattern.trim_start_matches("./").to_owned()
+            } else {
+                format!("**/{pattern}")
+            };
+            GlobBuilder::new(&pattern)
+                .literal_separator(true)
+                .build()
+                .map(|glob| glob.compile_matcher())
+                .map_err(|error| Error::InvalidOptions(error.to_string()))
+        })
+        .collect::<Result<_, _>>()?;
+    let options = match item.remove("options") {
+        Some(Value::Object(options)) => options,
+        None => Map::new(),
+        Some(_) => {
+            return Err(Error::InvalidOptions(
+                "`options` of overrides must be an object".into(),
+            ));
+        }
+    };
+    Ok(Override { globs, options })
+}
+
+/// Find the nearest config file by walking up from the directory of the given file.
+pub fn find_config_file(file: impl AsRef<Path>) -> Option<PathBuf> {
+    find_config_files(file).into_iter().next()
+}
+
+/// Find config files by walking up from the directory of the given file.
+/// There's at most one config file in each directory, and nearer ones come first.
+///
+/// Config files aren't loaded, so ones beyond the config file with `root = true`
+/// are also returned. Use [`options_from_config_files`] to merge loaded config files.
+pub fn find_config_files(file: impl AsRef<Path>) -> Vec<PathBuf> {
+    let file = file.as_ref();
+    let file = std::path::absolute(file).unwrap_or_else(|_| file.to_path_buf());
+    file.ancestors()
+        .skip(1)
+        .filter_map(|dir| {
+            CONFIG_FILE_NAMES
+                .iter()
+                .map(|name| dir.join(name))
+                .find(|path| path.is_file())
+        })
+        .collect()
+}
+
+/// Resolve options for the given file by merging config files,
+/// which should be sorted from the nearest one to the farthest one.
+/// Config files beyond the first one with `root = true` are ignored.
+///
+/// Options that aren't specified in any config file fall back to `fallback`.
+pub fn options_from_config_files<'a>(
+    configs: impl IntoIterator<Item = &'a ConfigFile>,
+    file: impl AsRef<Path>,
+    fallback: &PartialFormatOptions,
+) -> Result<FormatOptions, Error> {
+    options_with_fallbacks(merge_config_files(configs, file), &[fallback])
+}
+
+fn merge_config_files<'a>(
+    configs: impl IntoIterator<Item = &'a ConfigFile>,
+    file: impl AsRef<Path>,
+) -> Map<String, Value> {
+    let mut configs = configs.into_iter().collect::<Vec<_>>();
+    if let Some(root) = configs.iter().position(|config| config.root) {
+        configs.truncate(root + 1);
+    }
+    configs
+        .iter()
+        .rev()
+        .fold(Map::new(), |mut options, config| {
+            merge(&mut options, config.merge_options(&file));
+            options
+        })
+}
+
+/// Find config files of the given file, and resolve options for it.
+/// Layout options not specified in config files are derived from `.editorconfig` files,
+/// and default options will be used if neither is found.
+pub fn resolve_options(file: impl AsRef<Path>) -> Result<FormatOptions, Error> {
+    resolve_options_with_fallback(file, &PartialFormatOptions::default())
+}
+
+/// Resolve options like [`resolve_options`],
+/// but options specified by neither config files nor `.editorconfig` files fall back to `fallback`.
+///
+/// This is useful for editors, whose own settings should have the lowest precedence.
+pub fn resolve_options_with_fallback(
+    file: impl AsRef<Path>,
+    fallback: &PartialFormatOptions,
+) -> Result<FormatOptions, Error> {
+    let mut configs = vec![];
+    for path in find_config_files(&file) {
+        let config = ConfigFile::load(path)?;
+        let is_root = config.root;
+        configs.push(config);
+        if is_root {
+            break;
+        }
+    }
+    let editorconfig = editorconfig::layout_options(&file);
+    options_with_fallbacks(
+        merge_config_files(&configs, file),
+        &[fallback, &editorconfig],
+    )
+}
@@ -0,0 +1,57 @@
+use crate::range::get_blocks;
+use raffia::{
+    Spanned,
+    ast::{QualifiedRule, Statement, Stylesheet},
+};
+use std::borrow::Cow;
+
+/// Selector list of indented Sass can span multiple lines when lines end with commas,
+/// but parser treats line breaks as the end of selectors,
+/// so each of those lines is parsed as a qualified rule with a trailing comma and an empty block.
+/// Line breaks after those commas are replaced with spaces, so offsets won't change.
+pub(crate) fn join_sass_selector_lines<'s>(
+    input: &'s str,
+    stylesheet: &Stylesheet,
+) -> Cow<'s, str> {
+    let mut line_breaks = vec![];
+    find_selector_line_breaks(input, &stylesheet.statements, &mut line_breaks);
+    if line_breaks.is_empty() {
+        return Cow::Borrowed(input);
+    }
+    let mut bytes = input.as_bytes().to_vec();
+    line_breaks.into_iter().for_each(|offset| {
+        bytes[offset] = b' ';
+        if offset > 0 && bytes[offset - 1] == b'\r' {
+            bytes[offset - 1] = b' ';
+        }
+    });
+    // only ASCII line breaks are replaced with spaces, so it's still valid UTF-8
+    Cow::Owned(String::from_utf8(bytes).unwrap_or_default())
+}
+
+fn find_selector_line_breaks(input: &str, statements: &[Statement], line_breaks: &mut Vec<usize>) {
+    statements
+        .iter()
+        .enumerate()
+        .for_each(|(i, stmt)| match stmt {
+            Statement::QualifiedRule(QualifiedRule {
+                selector, block, ..
+            }) if block.statements.is_empty()
+                && selector.comma_spans.len() == selector.selectors.len() =>
+            {
+                let (Some(comma), Some(next)) =
+                    (selector.comma_spans.last(), statements.get(i + 1))
+                else {
+                    return;
+                };
+                // the following selector must start at the next line
+                let gap = &input[comma.end..next.span().start];
+                if gap.trim_ascii().is_empty() && gap.matches('\n').count() == 1 {
+                    line_breaks.extend(gap.find('\n').map(|offset| comma.end + offset));
+                }
+            }
+            _ => get_blocks(stmt).into_iter().for_each(|block| {
+                find_selector_line_breaks(input, &block.statements, line_breaks);
+            }),
+        });
+}
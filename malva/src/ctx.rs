@@ -1,21 +1,116 @@
-use crate::{LineBounds, config::LanguageOptions, doc_gen::format_comment};
+use crate::{
+    LineBounds,
+    config::{FormatOptions, LanguageOptions},
+    doc_gen::format_comment,
+    error::{Warning, WarningKind},
+};
 use raffia::{
-    Syntax,
+    Span, Syntax,
     token::{Comment, CommentKind},
 };
-use std::{array, iter::Peekable, mem};
+use std::{array, cell::RefCell, collections::HashSet, iter::Peekable, mem, rc::Rc};
 use tiny_pretty::Doc;
 
+/// Printer options can't be changed in the middle of printing,
+/// so top-level statements with different print width or indent kind
+/// are split into segments, and each segment is printed with its own options.
+#[derive(Default)]
+pub(crate) struct PrintSegments<'s> {
+    /// Docs that have been split, along with options for printing them.
+    pub finished: Vec<(Doc<'s>, Option<Rc<FormatOptions>>)>,
+    /// Options for printing docs after finished segments.
+    pub options: Option<Rc<FormatOptions>>,
+}
+
+impl<'s> PrintSegments<'s> {
+    /// Iterate all segments, where the given doc is the last one.
+    pub(crate) fn with_last(
+        self,
+        doc: Doc<'s>,
+    ) -> impl Iterator<Item = (Doc<'s>, Option<Rc<FormatOptions>>)> {
+        self.finished
+            .into_iter()
+            .chain(std::iter::once((doc, self.options)))
+    }
+}
+
 pub(crate) struct Ctx<'a, 's: 'a> {
     pub source: Option<&'s str>,
     pub syntax: Syntax,
     pub options: &'a LanguageOptions,
     pub comments: &'a [Comment<'s>],
     pub indent_width: usize,
-    pub line_bounds: LineBounds,
+    pub line_bounds: &'a LineBounds,
+    /// Start offsets of comments that have been printed.
+    pub printed_comments: &'a RefCell<HashSet<usize>>,
+    /// Options passed to formatter, which aren't overridden by comment directives.
+    pub initial_options: &'a FormatOptions,
+    /// Warnings found while generating docs.
+    pub warnings: &'a RefCell<Vec<Warning>>,
+    /// Top-level docs that are split by options override directives.
+    pub print_segments: &'a RefCell<PrintSegments<'s>>,
 }
 
 impl<'a, 's> Ctx<'a, 's> {
+    /// Create context with options that are overridden by comment directives.
+    pub(crate) fn with_options<'b>(&'b self, options: &'b FormatOptions) -> Ctx<'b, 's> {
+        Ctx {
+            source: self.source,
+            syntax: self.syntax,
+            options: &options.language,
+            comments: self.comments,
+            indent_width: options.layout.indent_width,
+            line_bounds: self.line_bounds,
+            printed_comments: self.printed_comments,
+            initial_options: self.initial_options,
+            warnings: self.warnings,
+            print_segments: self.print_segments,
+        }
+    }
+
+    /// Record a warning at the start of the span.
+    pub(crate) fn warn(&self, span: &Span, kind: WarningKind) {
+        let (line, col) = self.line_bounds.get_line_col(span.start);
+        self.warnings.borrow_mut().push(Warning {
+            kind,
+            span: span.clone(),
+            line,
+            col,
+        });
+    }
+
+    /// Record that the comment is printed.
+    pub(crate) fn mark_comment_printed(&self, comment: &Comment) {
+        if self.should_check_comments() {
+            self.printed_comments
+                .borrow_mut()
+                .insert(comment.span.start);
+        }
+    }
+
+    /// Record that comments in the span are printed as-is along with the original source.
+    pub(crate) fn mark_raw_printed(&self, start: usize, end: usize) {
+        if self.should_check_comments() {
+            let mut printed_comments = self.printed_comments.borrow_mut();
+            self.get_comments_between(start, end).for_each(|comment| {
+                printed_comments.insert(comment.span.start);
+            });
+        }
+    }
+
+    /// Lost comments are only checked in debug build or verify mode,
+    /// so printed comments don't need to be recorded otherwise.
+    pub(crate) fn should_check_comments(&self) -> bool {
+        cfg!(debug_assertions) || self.initial_options.verify
+    }
+
+    /// Find the first comment inside the span which hasn't been printed.
+    pub(crate) fn find_lost_comment(&self, span: &Span) -> Option<&Comment<'s>> {
+        let printed_comments = self.printed_comments.borrow();
+        self.get_comments_between(span.start, span.end)
+            .find(|comment| !printed_comments.contains(&comment.span.start))
+    }
+
     pub(crate) fn get_comments_between(
         &self,
         start: usize,
@@ -23,9 +118,14 @@ impl<'a, 's> Ctx<'a, 's> {
     ) -> impl Iterator<Item = &Comment<'s>> + Clone {
         debug_assert!(start <= end);
 
-        self.comments
+        // Comments are sorted and never overlap,
+        // so we can find the first one by binary search and stop at the first one out of range.
+        let first = self
+            .comments
+            .partition_point(|comment| comment.span.start < start);
+        self.comments[first..]
             .iter()
-            .filter(move |comment| comment.span.start >= start && comment.span.end <= end)
+            .take_while(move |comment| comment.span.end <= end)
     }
 
     pub(crate) fn start_spaced_comments(
@@ -6,6 +6,7 @@ use raffia::{
 use tiny_pretty::Doc;
 
 pub(crate) fn format_comment<'a, 's: 'a>(comment: &Comment<'s>, ctx: &Ctx<'a, 's>) -> Doc<'s> {
+    ctx.mark_comment_printed(comment);
     match comment.kind {
         CommentKind::Block => {
             let mut docs = vec![Doc::text("/*")];
@@ -31,6 +32,14 @@ pub(crate) fn format_comment<'a, 's: 'a>(comment: &Comment<'s>, ctx: &Ctx<'a, 's
                 trimmed.is_empty() || trimmed.starts_with('*')
             });
 
+            // In Sass, lines of comment must be indented deeper than the start of comment,
+            // which may not be satisfied when converting from other syntaxes.
+            let needs_indent = ctx.syntax == Syntax::Sass
+                && lines
+                    .clone()
+                    .skip(1)
+                    .any(|line| !line.trim().is_empty() && !line.starts_with([' ', '\t']));
+
             if is_jsdoc_like {
                 if let Some(first) = lines.next() {
                     docs.push(Doc::text(first));
@@ -38,7 +47,7 @@ pub(crate) fn format_comment<'a, 's: 'a>(comment: &Comment<'s>, ctx: &Ctx<'a, 's
                 docs.extend(
                     lines.map(|line| Doc::hard_line().append(Doc::text(line.trim_start()))),
                 );
-            } else if ctx.options.align_comments {
+            } else if ctx.options.align_comments || needs_indent {
                 docs.append(&mut reflow(comment, ctx));
             } else {
                 docs.extend(itertools::intersperse(
@@ -61,6 +70,8 @@ pub(crate) fn format_comment<'a, 's: 'a>(comment: &Comment<'s>, ctx: &Ctx<'a, 's
 
             if is_jsdoc_like {
                 Doc::list(docs).nest(1)
+            } else if needs_indent {
+                Doc::list(docs).nest(ctx.indent_width)
             } else {
                 Doc::list(docs)
             }
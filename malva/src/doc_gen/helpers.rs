@@ -22,12 +22,13 @@ where
         SelectorOverride::Ignore => {
             if let Some(source) = ctx.source {
                 let first = selectors[0].span();
-                let raw = &source[first.start
-                    ..selectors
-                        .last()
-                        .map(|last| last.span())
-                        .unwrap_or(first)
-                        .end];
+                let end = selectors
+                    .last()
+                    .map(|last| last.span())
+                    .unwrap_or(first)
+                    .end;
+                ctx.mark_raw_printed(first.start, end);
+                let raw = &source[first.start..end];
                 return Doc::list(
                     itertools::intersperse(raw.lines().map(Doc::text), Doc::empty_line()).collect(),
                 );
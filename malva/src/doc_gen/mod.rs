@@ -1,4 +1,7 @@
-pub(crate) use self::comment::format_comment;
+pub(crate) use self::{
+    comment::format_comment,
+    stmt::{apply_options_directives, format_statements, is_css_modules_export, strip_directive},
+};
 use crate::{ctx::Ctx, state::State};
 use tiny_pretty::Doc;
 
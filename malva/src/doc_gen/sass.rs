@@ -6,7 +6,7 @@ use super::{
     },
 };
 use crate::{ctx::Ctx, state::State};
-use raffia::{Spanned, ast::*};
+use raffia::{Spanned, Syntax, ast::*};
 use std::{iter, mem};
 use tiny_pretty::Doc;
 
@@ -385,13 +385,20 @@ impl<'a, 's: 'a> DocGen<'a, 's> for SassIfAtRule<'s> {
         docs.push(self.if_clause.doc(ctx, state));
         let mut pos = self.if_clause.span.end;
 
+        // there're no braces in Sass, so `@else` must be at the start of a new line
+        let separator = if ctx.syntax == Syntax::Sass {
+            Doc::hard_line()
+        } else {
+            Doc::space()
+        };
+
         docs.extend(
             self.else_if_clauses
                 .iter()
                 .zip(self.else_spans.iter())
                 .scan(&mut pos, |pos, (clause, elseif_span)| {
                     Some(
-                        iter::once(Doc::space())
+                        iter::once(separator.clone())
                             .chain(ctx.end_spaced_comments(ctx.get_comments_between(
                                 mem::replace(*pos, elseif_span.end),
                                 elseif_span.start,
@@ -411,7 +418,7 @@ impl<'a, 's: 'a> DocGen<'a, 's> for SassIfAtRule<'s> {
             self.else_clause.as_ref().zip(self.else_spans.last())
         {
             docs.reserve(3);
-            docs.push(Doc::space());
+            docs.push(separator);
             docs.extend(ctx.end_spaced_comments(ctx.get_comments_between(pos, else_span.start)));
             docs.push(Doc::text("@else"));
             docs.push(helpers::format_space_before_block(
@@ -911,7 +918,20 @@ impl<'a, 's: 'a> DocGen<'a, 's> for SassQualifiedName<'s> {
 
 impl<'a, 's: 'a> DocGen<'a, 's> for SassUnaryExpression<'s> {
     fn doc(&self, ctx: &Ctx<'a, 's>, state: &State) -> Doc<'s> {
-        self.op.doc(ctx, state).append(self.expr.doc(ctx, state))
+        let op = self.op.doc(ctx, state);
+        // `-` followed by identifier or function will be parsed as a single identifier
+        if matches!(self.op.kind, SassUnaryOperatorKind::Minus)
+            && matches!(
+                &*self.expr,
+                ComponentValue::Function(..)
+                    | ComponentValue::InterpolableIdent(..)
+                    | ComponentValue::SassUnaryExpression(..)
+            )
+        {
+            op.append(Doc::space()).append(self.expr.doc(ctx, state))
+        } else {
+            op.append(self.expr.doc(ctx, state))
+        }
     }
 }
 
@@ -3,7 +3,7 @@ use super::{
     str::{CssStrRawFormatter, format_str, is_preferred_quote_allowed},
 };
 use crate::{ctx::Ctx, state::State};
-use raffia::{Spanned, ast::*};
+use raffia::{Spanned, Syntax, ast::*};
 use std::mem;
 use tiny_pretty::Doc;
 
@@ -15,7 +15,7 @@ impl<'a, 's: 'a> DocGen<'a, 's> for AnPlusB {
             -1 => Doc::text("-n"),
             a => Doc::text(format!("{a}n")),
         };
-        if self.b == 0 {
+        let an_plus_b = if self.b == 0 {
             a
         } else {
             let sign = if self.b > 0 { '+' } else { '-' };
@@ -25,7 +25,14 @@ impl<'a, 's: 'a> DocGen<'a, 's> for AnPlusB {
                 Doc::text(format!("{sign}{}", self.b.abs()))
             };
             a.append(b)
-        }
+        };
+
+        // An+B is normalized, so comments between its parts are moved after it
+        let mut docs = vec![an_plus_b];
+        docs.extend(
+            ctx.start_spaced_comments(ctx.get_comments_between(self.span.start, self.span.end)),
+        );
+        Doc::list(docs)
     }
 }
 
@@ -642,7 +649,12 @@ impl<'a, 's: 'a> DocGen<'a, 's> for SelectorList<'s> {
 impl<'a, 's: 'a> DocGen<'a, 's> for TagNameSelector<'s> {
     fn doc(&self, ctx: &Ctx<'a, 's>, state: &State) -> Doc<'s> {
         let name = if let InterpolableIdent::Literal(ident) = &self.name.name {
-            Doc::text(ident.raw.to_ascii_lowercase())
+            // in Less, lowercased `ALL` will be treated as `all` keyword of `:extend()`
+            if ctx.syntax == Syntax::Less && ident.raw.eq_ignore_ascii_case("all") {
+                Doc::text(ident.raw)
+            } else {
+                Doc::text(ident.raw.to_ascii_lowercase())
+            }
         } else {
             self.name.doc(ctx, state)
         };
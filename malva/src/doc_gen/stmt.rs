@@ -1,6 +1,11 @@
 use super::{DocGen, comment::format_comment, helpers};
-use crate::{ctx::Ctx, state::State};
-use raffia::{Span, Spanned, Syntax, ast::*, token::TokenWithSpan};
+use crate::{config::FormatOptions, ctx::Ctx, state::State};
+use raffia::{
+    Span, Spanned, Syntax,
+    ast::*,
+    token::{Comment, TokenWithSpan},
+};
+use std::rc::Rc;
 use tiny_pretty::Doc;
 
 impl<'a, 's: 'a> DocGen<'a, 's> for Declaration<'s> {
@@ -123,7 +128,16 @@ impl<'a, 's: 'a> DocGen<'a, 's> for Declaration<'s> {
             _ => {
                 let mut iter = self.value.iter().enumerate().peekable();
 
-                if !matches!(iter.peek(), Some((_, ComponentValue::Function(..)))) {
+                // block of nested properties in indented Sass starts with a line break
+                let is_sass_nesting_decl = |value: &ComponentValue| {
+                    ctx.syntax == Syntax::Sass
+                        && matches!(value, ComponentValue::SassNestingDeclaration(..))
+                };
+                if !matches!(iter.peek(), Some((_, ComponentValue::Function(..))))
+                    && !iter
+                        .peek()
+                        .is_some_and(|(_, value)| is_sass_nesting_decl(value))
+                {
                     docs.push(space_after_colon);
                 }
 
@@ -215,6 +229,7 @@ impl<'a, 's: 'a> DocGen<'a, 's> for Declaration<'s> {
                                 | ComponentValue::Function(..),
                             ))
                             | None => {}
+                            Some((_, next)) if is_sass_nesting_decl(next) => {}
                             Some((
                                 _,
                                 ComponentValue::Delimiter(Delimiter {
@@ -263,23 +278,8 @@ impl<'a, 's: 'a> DocGen<'a, 's> for ImportantAnnotation<'s> {
 
 impl<'a, 's: 'a> DocGen<'a, 's> for QualifiedRule<'s> {
     fn doc(&self, ctx: &Ctx<'a, 's>, state: &State) -> Doc<'s> {
-        let mut keep_decl_name_case = false;
-        if let [ComplexSelector { children, .. }] = &self.selector.selectors[..]
-            && let [ComplexSelectorChild::CompoundSelector(CompoundSelector { children, .. })] =
-                &children[..]
-            && let [
-                SimpleSelector::PseudoClass(PseudoClassSelector {
-                    name: InterpolableIdent::Literal(Ident { raw: "export", .. }),
-                    arg: None,
-                    ..
-                }),
-            ] = &children[..]
-        {
-            // CSS modules
-            keep_decl_name_case = true;
-        }
         let state = State {
-            keep_decl_name_case,
+            keep_decl_name_case: is_css_modules_export(&self.selector),
             ..state.clone()
         };
         // we don't use `SelectorList::doc` here
@@ -336,7 +336,11 @@ impl<'a, 's: 'a> DocGen<'a, 's> for SimpleBlock<'s> {
             &self.span,
             line_break_doc.clone(),
             ctx,
-            state,
+            &State {
+                indent: state.indent + ctx.indent_width,
+                top_level: false,
+                ..state.clone()
+            },
         );
 
         let has_stmts = !stmt_docs.is_empty();
@@ -401,12 +405,26 @@ impl<'a, 's: 'a> DocGen<'a, 's> for Stylesheet<'s> {
             // but parser allows them and treat them as recoverable errors.
             // This situation can happen when formatting declarations
             // inside `style` attribute in HTML.
-            // All comments are ignored.
-            stmt_docs = itertools::intersperse(
-                self.statements.iter().map(|stmt| stmt.doc(ctx, state)),
-                Doc::text("; "),
-            )
-            .collect();
+            // Line comments don't exist in CSS, so comments can be printed in the same line.
+            let mut pos = self.span.start;
+            self.statements.iter().enumerate().for_each(|(i, stmt)| {
+                if i > 0 {
+                    stmt_docs.push(Doc::text("; "));
+                }
+                let span = stmt.span();
+                ctx.get_comments_between(pos, span.start)
+                    .for_each(|comment| {
+                        stmt_docs.push(format_comment(comment, ctx));
+                        stmt_docs.push(Doc::space());
+                    });
+                stmt_docs.push(stmt.doc(ctx, state));
+                pos = span.end;
+            });
+            ctx.get_comments_between(pos, self.span.end)
+                .for_each(|comment| {
+                    stmt_docs.push(Doc::space());
+                    stmt_docs.push(format_comment(comment, ctx));
+                });
         } else {
             format_statements(
                 &mut stmt_docs,
@@ -423,7 +441,27 @@ impl<'a, 's: 'a> DocGen<'a, 's> for Stylesheet<'s> {
     }
 }
 
-fn format_statements<'a, 's: 'a>(
+/// Check if selector is `:export` of CSS modules,
+/// whose declaration names are case sensitive.
+pub(crate) fn is_css_modules_export(selector: &SelectorList) -> bool {
+    if let [ComplexSelector { children, .. }] = &selector.selectors[..]
+        && let [ComplexSelectorChild::CompoundSelector(CompoundSelector { children, .. })] =
+            &children[..]
+        && let [
+            SimpleSelector::PseudoClass(PseudoClassSelector {
+                name: InterpolableIdent::Literal(Ident { raw: "export", .. }),
+                arg: None,
+                ..
+            }),
+        ] = &children[..]
+    {
+        true
+    } else {
+        false
+    }
+}
+
+pub(crate) fn format_statements<'a, 's: 'a>(
     docs: &mut Vec<Doc<'s>>,
     statements: &[Statement<'s>],
     outer_span: &Span,
@@ -436,30 +474,46 @@ fn format_statements<'a, 's: 'a>(
     docs.reserve(statements.len() * 2);
 
     let mut pos = outer_span.start;
-    let mut stmts = statements.iter().peekable();
+    let mut stmts = statements.iter();
+    let block_state = state;
+    let mut state = state.clone();
 
     if let Some(declaration_order) = &ctx.options.declaration_order {
         let mut sortable_decls = Vec::with_capacity(3);
         let mut is_first_stmt_or_decls_group = true;
 
         while let Some(stmt) = stmts.next() {
-            let next_stmt = stmts.peek();
-            if let Statement::Declaration(Declaration {
-                name: InterpolableIdent::Literal(ident),
-                ..
-            }) = stmt
+            apply_options_directives(pos, stmt.span().start, ctx, &mut state);
+            let options_override = state.options_override.clone();
+            let scoped_ctx = is_options_overridden(block_state, &state).then(|| {
+                ctx.with_options(options_override.as_deref().unwrap_or(ctx.initial_options))
+            });
+            let ctx = scoped_ctx.as_ref().unwrap_or(ctx);
+            // sorted declarations will be moved, so they can't be split
+            if sortable_decls.is_empty() {
+                split_print_segment(docs, &state, ctx);
+            }
+
+            let ignored_until = take_range_ignored_stmts(stmt, &mut stmts, pos, ctx);
+            let next_stmt = stmts.as_slice().first();
+            if ignored_until.is_none()
+                && let Statement::Declaration(Declaration {
+                    name: InterpolableIdent::Literal(ident),
+                    ..
+                }) = stmt
             {
                 sortable_decls.push((
                     &*ident.name,
                     SingleStmtFormatter {
                         stmt,
-                        next_stmt: next_stmt.copied(),
+                        next_stmt,
                         pos: &mut pos,
                         outer_span,
                         ignore_leading_whitespace: true,
+                        ignored_until: None,
                         line_break_doc: line_break_doc.clone(),
                     }
-                    .format(ctx, state),
+                    .format(ctx, &state),
                 ));
                 let is_grouped_by_empty_line = matches!(
                     ctx.options.declaration_order_group_by,
@@ -480,6 +534,8 @@ fn format_statements<'a, 's: 'a>(
                         ..
                     }))
                 ) || is_grouped_by_empty_line
+                    || next_stmt
+                        .is_some_and(|next| has_ignore_start_directive(pos, next.span().start, ctx))
                 {
                     use crate::{config::DeclarationOrder, helpers::sort_decl};
                     match declaration_order {
@@ -495,19 +551,41 @@ fn format_statements<'a, 's: 'a>(
                             sortable_decls
                                 .sort_by(|(a, _), (b, _)| sort_decl::compare_in_concentric(a, b));
                         }
+                        DeclarationOrder::Custom { order, unspecified } => {
+                            sortable_decls.sort_by(|(a, _), (b, _)| {
+                                sort_decl::compare_in_custom(a, b, order, unspecified)
+                            });
+                        }
                     }
                     if is_first_stmt_or_decls_group {
                         is_first_stmt_or_decls_group = false;
                     } else {
                         docs.push(Doc::hard_line());
                     }
-                    docs.extend(
-                        itertools::intersperse(
-                            sortable_decls.drain(..).map(|(_, docs)| docs),
-                            vec![line_break_doc.clone()],
-                        )
-                        .flatten(),
-                    );
+                    let get_group = |property| match declaration_order {
+                        DeclarationOrder::Smacss => sort_decl::group_in_smacss(property),
+                        DeclarationOrder::Concentric => sort_decl::group_in_concentric(property),
+                        _ => None,
+                    };
+                    // unknown properties don't belong to any groups, so they won't be separated
+                    let mut prev_group = None;
+                    for (i, (property, mut decl_docs)) in sortable_decls.drain(..).enumerate() {
+                        let group = ctx
+                            .options
+                            .declaration_order_separate_groups
+                            .then(|| get_group(property))
+                            .flatten();
+                        if i > 0 {
+                            if prev_group.is_some() && group.is_some() && prev_group != group {
+                                docs.push(Doc::empty_line());
+                                docs.push(Doc::hard_line());
+                            } else {
+                                docs.push(line_break_doc.clone());
+                            }
+                        }
+                        docs.append(&mut decl_docs);
+                        prev_group = group.or(prev_group);
+                    }
                     if is_grouped_by_empty_line {
                         docs.push(Doc::empty_line());
                     }
@@ -516,29 +594,40 @@ fn format_statements<'a, 's: 'a>(
                 docs.append(
                     &mut SingleStmtFormatter {
                         stmt,
-                        next_stmt: next_stmt.copied(),
+                        next_stmt,
                         pos: &mut pos,
                         outer_span,
                         ignore_leading_whitespace: false,
+                        ignored_until,
                         line_break_doc: line_break_doc.clone(),
                     }
-                    .format(ctx, state),
+                    .format(ctx, &state),
                 );
                 is_first_stmt_or_decls_group = false;
             }
         }
     } else {
         while let Some(stmt) = stmts.next() {
+            apply_options_directives(pos, stmt.span().start, ctx, &mut state);
+            let options_override = state.options_override.clone();
+            let scoped_ctx = is_options_overridden(block_state, &state).then(|| {
+                ctx.with_options(options_override.as_deref().unwrap_or(ctx.initial_options))
+            });
+            let ctx = scoped_ctx.as_ref().unwrap_or(ctx);
+            split_print_segment(docs, &state, ctx);
+
+            let ignored_until = take_range_ignored_stmts(stmt, &mut stmts, pos, ctx);
             docs.append(
                 &mut SingleStmtFormatter {
                     stmt,
-                    next_stmt: stmts.peek().copied(),
+                    next_stmt: stmts.as_slice().first(),
                     pos: &mut pos,
                     outer_span,
                     ignore_leading_whitespace: false,
+                    ignored_until,
                     line_break_doc: line_break_doc.clone(),
                 }
-                .format(ctx, state),
+                .format(ctx, &state),
             );
         }
     }
@@ -560,12 +649,187 @@ fn format_statements<'a, 's: 'a>(
         });
 }
 
+/// Check if there's an ignore start directive between the given positions,
+/// and it isn't closed by an ignore end directive.
+fn has_ignore_start_directive(start: usize, end: usize, ctx: &Ctx) -> bool {
+    ctx.get_comments_between(start, end)
+        .filter(|comment| {
+            is_directive(comment, &ctx.options.ignore_start_comment_directive)
+                || is_directive(comment, &ctx.options.ignore_end_comment_directive)
+        })
+        .last()
+        .is_some_and(|comment| is_directive(comment, &ctx.options.ignore_start_comment_directive))
+}
+
+/// If the given statement follows an ignore start directive,
+/// consume statements until ignore end directive or the end of block,
+/// then return the last ignored statement.
+fn take_range_ignored_stmts<'a, 's>(
+    stmt: &'a Statement<'s>,
+    stmts: &mut std::slice::Iter<'a, Statement<'s>>,
+    pos: usize,
+    ctx: &Ctx,
+) -> Option<&'a Statement<'s>> {
+    // ignored statements are printed from source code, so it's required
+    if ctx.source.is_none() || !has_ignore_start_directive(pos, stmt.span().start, ctx) {
+        return None;
+    }
+    let mut last = stmt;
+    while let Some(next) = stmts.as_slice().first() {
+        if find_ignore_end_directive(last.span().end, next.span().start, ctx).is_some() {
+            break;
+        }
+        last = next;
+        stmts.next();
+    }
+    Some(last)
+}
+
+fn find_ignore_end_directive<'c, 's>(
+    start: usize,
+    end: usize,
+    ctx: &'c Ctx<'_, 's>,
+) -> Option<&'c Comment<'s>> {
+    ctx.get_comments_between(start, end)
+        .find(|comment| is_directive(comment, &ctx.options.ignore_end_comment_directive))
+}
+
+/// Print source code as-is.
+fn print_raw<'s>(docs: &mut Vec<Doc<'s>>, source: &'s str, start: usize, end: usize, ctx: &Ctx) {
+    ctx.mark_raw_printed(start, end);
+    docs.extend(itertools::intersperse(
+        source[start..end].lines().map(Doc::text),
+        Doc::empty_line(),
+    ));
+}
+
+/// Check if comment starts with the given directive,
+/// which can be followed by whitespace and other text.
+fn is_directive(comment: &Comment, directive: &str) -> bool {
+    strip_directive(comment, directive).is_some()
+}
+
+/// Return the rest of comment after the given directive.
+pub(crate) fn strip_directive<'s>(comment: &Comment<'s>, directive: &str) -> Option<&'s str> {
+    comment
+        .content
+        .trim_start()
+        .strip_prefix(directive)
+        .filter(|rest| rest.is_empty() || rest.starts_with(|c: char| c.is_ascii_whitespace()))
+}
+
+/// Apply options override directives and reset directives between the given positions.
+/// Directives that can't be applied will be reported as warnings.
+pub(crate) fn apply_options_directives(start: usize, end: usize, ctx: &Ctx, state: &mut State) {
+    use crate::{config::PartialFormatOptions, error::WarningKind};
+
+    ctx.get_comments_between(start, end).for_each(|comment| {
+        let current = state
+            .options_override
+            .as_deref()
+            .unwrap_or(ctx.initial_options);
+        let is_reset = is_directive(comment, &ctx.options.options_reset_comment_directive);
+        let mut options = if is_reset {
+            ctx.initial_options.clone()
+        } else if let Some(pairs) =
+            strip_directive(comment, &ctx.options.options_override_comment_directive)
+        {
+            match PartialFormatOptions::from_directive(pairs) {
+                Ok(partial) => {
+                    let mut options = current.clone();
+                    partial.apply_to(&mut options);
+                    options
+                }
+                Err(message) => {
+                    ctx.warn(&comment.span, WarningKind::InvalidDirective(message));
+                    return;
+                }
+            }
+        } else {
+            return;
+        };
+
+        if std::mem::discriminant(&options.layout.line_break)
+            != std::mem::discriminant(&current.layout.line_break)
+        {
+            options.layout.line_break = current.layout.line_break.clone();
+            if !is_reset {
+                ctx.warn(
+                    &comment.span,
+                    WarningKind::InvalidDirective("`lineBreak` can't be overridden".into()),
+                );
+            }
+        }
+        // printer options of nested statements are decided by top-level statements
+        if !state.top_level && is_layout_changed(&options, current) {
+            options.layout.print_width = current.layout.print_width;
+            options.layout.use_tabs = current.layout.use_tabs;
+            if !is_reset {
+                ctx.warn(
+                    &comment.span,
+                    WarningKind::InvalidDirective(
+                        "`printWidth` and `useTabs` can only be overridden at the top level".into(),
+                    ),
+                );
+            }
+        }
+        if options.layout.indent_width == 0 {
+            ctx.warn(
+                &comment.span,
+                WarningKind::InvalidDirective("`indentWidth` must be greater than 0".into()),
+            );
+        } else if is_reset && !is_layout_changed(&options, ctx.initial_options) {
+            state.options_override = None;
+        } else {
+            state.options_override = Some(Rc::new(options));
+        }
+    });
+}
+
+/// Check if options are overridden or reset by directives in current block.
+fn is_options_overridden(block_state: &State, state: &State) -> bool {
+    match (&block_state.options_override, &state.options_override) {
+        (Some(a), Some(b)) => !Rc::ptr_eq(a, b),
+        (None, None) => false,
+        _ => true,
+    }
+}
+
+/// Check if options require different printer options.
+fn is_layout_changed(a: &FormatOptions, b: &FormatOptions) -> bool {
+    a.layout.print_width != b.layout.print_width || a.layout.use_tabs != b.layout.use_tabs
+}
+
+/// If printer options are changed by directives before the following top-level statement,
+/// split docs before it into a segment, which will be printed with previous options.
+fn split_print_segment<'s>(docs: &mut Vec<Doc<'s>>, state: &State, ctx: &Ctx<'_, 's>) {
+    if !state.top_level {
+        return;
+    }
+    let mut segments = ctx.print_segments.borrow_mut();
+    let options = state
+        .options_override
+        .as_deref()
+        .unwrap_or(ctx.initial_options);
+    let segment_options = segments.options.as_deref().unwrap_or(ctx.initial_options);
+    if is_layout_changed(options, segment_options) {
+        let segment_options =
+            std::mem::replace(&mut segments.options, state.options_override.clone());
+        segments
+            .finished
+            .push((Doc::list(std::mem::take(docs)), segment_options));
+    }
+}
+
 struct SingleStmtFormatter<'a, 's: 'a> {
     stmt: &'a Statement<'s>,
     next_stmt: Option<&'a Statement<'s>>,
     pos: &'a mut usize,
     outer_span: &'a Span,
     ignore_leading_whitespace: bool,
+    /// The last statement enclosed by ignore range directives.
+    /// Statements from `stmt` to this will be printed as-is.
+    ignored_until: Option<&'a Statement<'s>>,
     line_break_doc: Doc<'s>,
 }
 impl<'a, 's: 'a> SingleStmtFormatter<'a, 's> {
@@ -628,33 +892,34 @@ impl<'a, 's: 'a> SingleStmtFormatter<'a, 's> {
         } else {
             state
         };
-        if comments
-            .last()
-            .and_then(|comment| {
-                comment
-                    .content
-                    .trim_start()
-                    .strip_prefix(&ctx.options.ignore_comment_directive)
-            })
-            .is_some_and(|rest| {
-                rest.is_empty() || rest.starts_with(|c: char| c.is_ascii_whitespace())
-            })
+        let stmt = self.ignored_until.unwrap_or(self.stmt);
+        let next_start = self
+            .next_stmt
+            .map(|next| next.span().start)
+            .unwrap_or_else(|| self.outer_span.end);
+        let mut has_end_directive = false;
+        if let Some(source) = ctx.source
+            && (self.ignored_until.is_some()
+                || comments.last().is_some_and(|comment| {
+                    is_directive(comment, &ctx.options.ignore_comment_directive)
+                }))
         {
-            if let Some(source) = ctx.source {
-                docs.extend(itertools::intersperse(
-                    source[span.start..span.end].lines().map(Doc::text),
-                    Doc::empty_line(),
-                ));
-            } else {
-                docs.push(self.stmt.doc(ctx, state));
-            }
+            // end directive is printed as-is along with ignored statements,
+            // otherwise it may be moved away when sorting declarations
+            let end_directive = self
+                .ignored_until
+                .and_then(|stmt| find_ignore_end_directive(stmt.span().end, next_start, ctx));
+            has_end_directive = end_directive.is_some();
+            let end = end_directive.map_or(stmt.span().end, |comment| comment.span.end);
+            print_raw(&mut docs, source, span.start, end, ctx);
+            *self.pos = end;
         } else {
             docs.push(self.stmt.doc(ctx, state));
+            *self.pos = span.end;
         }
-        *self.pos = span.end;
 
-        if ctx.syntax != Syntax::Sass {
-            match self.stmt {
+        if ctx.syntax != Syntax::Sass && !has_end_directive {
+            match stmt {
                 Statement::AtRule(at_rule) if at_rule.block.is_none() => docs.push(Doc::char(';')),
                 Statement::Declaration(decl)
                     if !matches!(
@@ -679,21 +944,16 @@ impl<'a, 's: 'a> SingleStmtFormatter<'a, 's> {
             }
         }
 
-        ctx.get_comments_between(
-            *self.pos,
-            self.next_stmt
-                .map(|next| next.span().start)
-                .unwrap_or_else(|| self.outer_span.end),
-        )
-        .for_each(|comment| {
-            if *self.pos > self.outer_span.start
-                && ctx.line_bounds.line_distance(*self.pos, comment.span.start) == 0
-            {
-                docs.push(Doc::space());
-                docs.push(format_comment(comment, ctx));
-                *self.pos = comment.span.end;
-            }
-        });
+        ctx.get_comments_between(*self.pos, next_start)
+            .for_each(|comment| {
+                if *self.pos > self.outer_span.start
+                    && ctx.line_bounds.line_distance(*self.pos, comment.span.start) == 0
+                {
+                    docs.push(Doc::space());
+                    docs.push(format_comment(comment, ctx));
+                    *self.pos = comment.span.end;
+                }
+            });
 
         docs
     }
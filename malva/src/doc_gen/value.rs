@@ -699,11 +699,13 @@ fn format_number_raw<'a, 's: 'a>(raw: &'s str, ctx: &Ctx<'a, 's>) -> Cow<'s, str
     };
 
     if let Some((coefficient, exponent)) = number.split_once(['e', 'E']) {
-        format!(
-            "{}e{exponent}",
-            coefficient.strip_suffix('.').unwrap_or(coefficient)
-        )
-        .into()
+        let coefficient = coefficient.strip_suffix('.').unwrap_or(coefficient);
+        // coefficient like `0.` may become empty after omitting leading zero
+        if coefficient.is_empty() || coefficient == "-" || coefficient == "+" {
+            format!("{coefficient}0e{exponent}").into()
+        } else {
+            format!("{coefficient}e{exponent}").into()
+        }
     } else {
         number
     }
@@ -0,0 +1,184 @@
+use crate::range::get_blocks;
+use raffia::{Span, Spanned, ast::*};
+use std::ops::Range;
+
+/// A text edit that replaces a range of original source code with new text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    /// Byte range of original source code.
+    pub range: Range<usize>,
+    /// Text that replaces the range, which is a part of formatted code.
+    pub new_text: String,
+}
+
+impl TextEdit {
+    /// Compute single edit that turns `old` into `new` by trimming common prefix and suffix.
+    /// `None` will be returned if they're the same.
+    pub fn diff(old: &str, new: &str) -> Option<TextEdit> {
+        let mut collector = EditsCollector {
+            old,
+            new,
+            edits: vec![],
+        };
+        collector.diff_text(0..old.len(), 0..new.len());
+        collector.edits.pop()
+    }
+}
+
+/// Compute text edits by comparing statements from original source code
+/// and statements from formatted code.
+///
+/// Statements will be compared recursively if they have same structure;
+/// otherwise, the whole statement will be replaced.
+pub(crate) struct EditsCollector<'a> {
+    pub(crate) old: &'a str,
+    pub(crate) new: &'a str,
+    pub(crate) edits: Vec<TextEdit>,
+}
+
+impl EditsCollector<'_> {
+    pub(crate) fn diff_statements(
+        &mut self,
+        old_stmts: &[Statement],
+        old_span: &Span,
+        new_stmts: &[Statement],
+        new_span: &Span,
+    ) {
+        if old_stmts.len() != new_stmts.len() {
+            self.diff_text(old_span.start..old_span.end, new_span.start..new_span.end);
+            return;
+        }
+
+        let mut old_pos = old_span.start;
+        let mut new_pos = new_span.start;
+        for (old_stmt, new_stmt) in old_stmts.iter().zip(new_stmts) {
+            let old_stmt_span = old_stmt.span();
+            let new_stmt_span = new_stmt.span();
+            self.diff_text(old_pos..old_stmt_span.start, new_pos..new_stmt_span.start);
+            self.diff_statement(old_stmt, new_stmt);
+            old_pos = old_stmt_span.end;
+            new_pos = new_stmt_span.end;
+        }
+        self.diff_text(old_pos..old_span.end, new_pos..new_span.end);
+    }
+
+    fn diff_statement(&mut self, old_stmt: &Statement, new_stmt: &Statement) {
+        let old_stmt_span = old_stmt.span();
+        let new_stmt_span = new_stmt.span();
+        let old_blocks = get_blocks(old_stmt);
+        let new_blocks = get_blocks(new_stmt);
+        if old_blocks.is_empty()
+            || old_blocks.len() != new_blocks.len()
+            || old_blocks
+                .iter()
+                .zip(&new_blocks)
+                .any(|(old, new)| old.statements.len() != new.statements.len())
+        {
+            self.diff_text(
+                old_stmt_span.start..old_stmt_span.end,
+                new_stmt_span.start..new_stmt_span.end,
+            );
+            return;
+        }
+
+        let mut old_pos = old_stmt_span.start;
+        let mut new_pos = new_stmt_span.start;
+        for (old_block, new_block) in old_blocks.iter().zip(&new_blocks) {
+            self.diff_text(old_pos..old_block.span.start, new_pos..new_block.span.start);
+            self.diff_statements(
+                &old_block.statements,
+                &old_block.span,
+                &new_block.statements,
+                &new_block.span,
+            );
+            old_pos = old_block.span.end;
+            new_pos = new_block.span.end;
+        }
+        self.diff_text(old_pos..old_stmt_span.end, new_pos..new_stmt_span.end);
+    }
+
+    /// Compare two pieces of text and record an edit without common prefix and suffix.
+    pub(crate) fn diff_text(&mut self, old_range: Range<usize>, new_range: Range<usize>) {
+        let old = &self.old[old_range.clone()];
+        let new = &self.new[new_range];
+        if old == new {
+            return;
+        }
+
+        let prefix_len = old
+            .char_indices()
+            .zip(new.chars())
+            .find(|((_, a), b)| a != b)
+            .map(|((i, _), _)| i)
+            .unwrap_or_else(|| old.len().min(new.len()));
+        let (old, new) = (&old[prefix_len..], &new[prefix_len..]);
+        let suffix_len = old
+            .char_indices()
+            .rev()
+            .zip(new.chars().rev())
+            .find(|((_, a), b)| a != b)
+            .map(|((i, a), _)| old.len() - i - a.len_utf8())
+            .unwrap_or_else(|| old.len().min(new.len()));
+
+        let start = old_range.start + prefix_len;
+        self.edits.push(TextEdit {
+            range: start..start + old.len() - suffix_len,
+            new_text: new[..new.len() - suffix_len].to_owned(),
+        });
+    }
+}
+
+/// Map an offset of original source code to the corresponding offset of edited code.
+///
+/// If the offset is inside an edit, non-whitespace characters before the offset
+/// in that edit will be counted, then the same number of non-whitespace characters
+/// will be skipped in new text.
+/// This is only an approximation: it assumes the edit only changes whitespaces,
+/// so the result may not point to the same character if characters are added,
+/// removed or reordered (such as changing quotes or sorting declarations).
+///
+/// Offset out of bounds or inside a multi-byte character will be moved back
+/// to the nearest character boundary.
+pub(crate) fn map_offset(old: &str, edits: &[TextEdit], offset: usize) -> usize {
+    let offset = floor_char_boundary(old, offset);
+    let mut removed = 0;
+    let mut inserted = 0;
+    for edit in edits {
+        let Range { start, end } = edit.range;
+        if offset < start {
+            break;
+        }
+        if offset >= end && offset > start {
+            removed += end - start;
+            inserted += edit.new_text.len();
+            continue;
+        }
+
+        let count = old[start..offset]
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .count();
+        let mut index = if count == 0 {
+            0
+        } else {
+            edit.new_text
+                .char_indices()
+                .filter(|(_, c)| !c.is_whitespace())
+                .nth(count - 1)
+                .map_or(edit.new_text.len(), |(i, c)| i + c.len_utf8())
+        };
+        // keep cursor sticking to the following character
+        if old[offset..].starts_with(|c: char| !c.is_whitespace()) {
+            index += edit.new_text[index..].len() - edit.new_text[index..].trim_start().len();
+        }
+        return start - removed + inserted + index;
+    }
+    offset - removed + inserted
+}
+
+pub(crate) fn floor_char_boundary(text: &str, offset: usize) -> usize {
+    (0..=offset.min(text.len()))
+        .rev()
+        .find(|i| text.is_char_boundary(*i))
+        .unwrap_or_default()
+}
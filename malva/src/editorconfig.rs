@@ -0,0 +1,156 @@
+//! Derive layout options from [EditorConfig](https://editorconfig.org) files.
+//!
+//! Supported properties are:
+//!
+//! - `indent_style` as [`useTabs`](https://malva.netlify.app/config/use-tabs.html)
+//! - `indent_size` and `tab_width` as [`indentWidth`](https://malva.netlify.app/config/indent-width.html)
+//! - `end_of_line` as [`lineBreak`](https://malva.netlify.app/config/line-break.html)
+//! - `max_line_length` as [`printWidth`](https://malva.netlify.app/config/print-width.html)
+//!
+//! Other properties and unsupported values (such as `end_of_line = cr`) are ignored.
+
+use crate::config::{LineBreak, PartialFormatOptions};
+use globset::GlobBuilder;
+use std::{collections::HashMap, fs, path::Path};
+
+/// Collect `.editorconfig` files from directory of the given file to its ancestors
+/// (until one with `root = true`), then derive layout options from sections matching that file.
+///
+/// Only layout options will be set in the returned value.
+pub fn layout_options(file: impl AsRef<Path>) -> PartialFormatOptions {
+    let file = file.as_ref();
+    let file = std::path::absolute(file).unwrap_or_else(|_| file.to_path_buf());
+
+    let mut files = vec![];
+    for dir in file.ancestors().skip(1) {
+        let Ok(content) = fs::read_to_string(dir.join(".editorconfig")) else {
+            continue;
+        };
+        let editorconfig = parse(&content);
+        let is_root = editorconfig.is_root;
+        files.push((dir, editorconfig));
+        if is_root {
+            break;
+        }
+    }
+
+    let mut properties = HashMap::new();
+    files.iter().rev().for_each(|(dir, editorconfig)| {
+        let Ok(relative) = file.strip_prefix(dir) else {
+            return;
+        };
+        editorconfig
+            .sections
+            .iter()
+            .filter(|section| section.is_match(relative))
+            .flat_map(|section| &section.properties)
+            .for_each(|(key, value)| {
+                if value == "unset" {
+                    properties.remove(key);
+                } else {
+                    properties.insert(key.clone(), value.clone());
+                }
+            });
+    });
+
+    to_options(&properties)
+}
+
+struct EditorConfig {
+    is_root: bool,
+    sections: Vec<Section>,
+}
+
+struct Section {
+    glob: String,
+    properties: Vec<(String, String)>,
+}
+
+impl Section {
+    fn is_match(&self, path: &Path) -> bool {
+        let glob = if let Some(glob) = self.glob.strip_prefix('/') {
+            glob.to_string()
+        } else if self.glob.contains('/') {
+            self.glob.clone()
+        } else {
+            format!("**/{}", self.glob)
+        };
+        // `**` of EditorConfig matches any characters including slashes,
+        // while in globset it must be a whole path component
+        let glob = glob
+            .split('/')
+            .map(|component| match component.strip_prefix("**") {
+                Some(rest) if !rest.is_empty() => format!("**/*{rest}"),
+                _ => component.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        GlobBuilder::new(&glob)
+            .literal_separator(true)
+            .build()
+            .is_ok_and(|glob| glob.compile_matcher().is_match(path))
+    }
+}
+
+fn parse(content: &str) -> EditorConfig {
+    let mut editorconfig = EditorConfig {
+        is_root: false,
+        sections: vec![],
+    };
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(glob) = line
+            .strip_prefix('[')
+            .and_then(|line| line.rsplit_once(']'))
+            .map(|(glob, _)| glob)
+        {
+            editorconfig.sections.push(Section {
+                glob: glob.to_string(),
+                properties: vec![],
+            });
+        } else if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim().to_ascii_lowercase();
+            if let Some(section) = editorconfig.sections.last_mut() {
+                section.properties.push((key, value));
+            } else if key == "root" {
+                editorconfig.is_root = value == "true";
+            }
+        }
+    }
+    editorconfig
+}
+
+fn to_options(properties: &HashMap<String, String>) -> PartialFormatOptions {
+    let tab_width = properties
+        .get("tab_width")
+        .and_then(|value| value.parse::<usize>().ok());
+    let indent_width = match properties.get("indent_size").map(|value| &**value) {
+        Some("tab") | None => tab_width,
+        Some(value) => value.parse().ok(),
+    };
+    PartialFormatOptions {
+        print_width: properties
+            .get("max_line_length")
+            .and_then(|value| value.parse().ok()),
+        use_tabs: properties
+            .get("indent_style")
+            .and_then(|value| match &**value {
+                "tab" => Some(true),
+                "space" => Some(false),
+                _ => None,
+            }),
+        indent_width: indent_width.filter(|width| *width > 0),
+        line_break: properties
+            .get("end_of_line")
+            .and_then(|value| match &**value {
+                "lf" => Some(LineBreak::Lf),
+                "crlf" => Some(LineBreak::Crlf),
+                _ => None,
+            }),
+        ..Default::default()
+    }
+}
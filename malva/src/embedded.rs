@@ -0,0 +1,709 @@
+//! Format style sheets embedded in other languages.
+//!
+//! [`format_markup`] handles HTML-like files, such as HTML, Vue, Svelte and Astro.
+//! It formats `<style>` blocks and `style` attributes, and leaves everything else untouched.
+//!
+//! [`format_template_literal`] handles CSS-in-JS tagged template literals,
+//! such as templates of styled-components and emotion.
+//!
+//! [`format_markdown`] handles fenced code blocks in Markdown.
+
+use crate::{
+    ColumnUnit, Error, LineBounds, Syntax, Warning, WarningKind,
+    config::{FormatOptions, LineBreak, Quotes},
+    format_text,
+};
+use raffia::Span;
+use std::{iter, ops::Range};
+
+/// Format `<style>` blocks and `style` attributes in HTML-like files,
+/// such as HTML, Vue, Svelte and Astro.
+///
+/// Syntax of `<style>` block is detected by its `lang` attribute (or `type` attribute).
+/// Blocks in unknown languages are kept as-is.
+/// Formatted code of `<style>` block keeps the indentation of original code.
+///
+/// Declarations in `style` attributes are printed in single line.
+/// Attributes that contain template expressions (such as `{color}` in Svelte) are kept as-is.
+///
+/// Errors point to the location in the whole file.
+pub fn format_markup(input: &str, options: &FormatOptions) -> Result<String, Error> {
+    let line_bounds = LineBounds::new(input);
+    let relocate = |error: Error, offset: usize| {
+        relocate_error(error, |pos| pos + offset, input, &line_bounds)
+    };
+    let mut output = String::with_capacity(input.len());
+    let mut copied = 0;
+    let mut pos = 0;
+
+    while let Some(start) = input[pos..].find('<').map(|i| pos + i) {
+        if input[start..].starts_with("<!--") {
+            pos = input[start..]
+                .find("-->")
+                .map_or(input.len(), |i| start + i + 3);
+            continue;
+        }
+        let Some(tag) = parse_tag(input, start) else {
+            pos = start + 1;
+            continue;
+        };
+        pos = tag.end;
+
+        for attr in &tag.attrs {
+            if let Some(value) = &attr.value
+                && input[attr.name.clone()].eq_ignore_ascii_case("style")
+                && let Some(quote) = value.quote
+                && !input[value.range.clone()].trim().is_empty()
+                && !input[value.range.clone()].contains('{')
+            {
+                let formatted = format_style_attr(&input[value.range.clone()], quote, options)
+                    .map_err(|error| relocate(error, value.range.start))?;
+                output.push_str(&input[copied..value.range.start]);
+                output.push_str(&formatted);
+                copied = value.range.end;
+            }
+        }
+
+        let name = &input[tag.name.clone()];
+        if tag.self_closing
+            || !(name.eq_ignore_ascii_case("style") || name.eq_ignore_ascii_case("script"))
+        {
+            continue;
+        }
+        // content of `<script>` is skipped, so `style` in code won't be touched
+        let content_end = find_closing_tag(input, tag.end, name);
+        pos = content_end;
+        if !name.eq_ignore_ascii_case("style") {
+            continue;
+        }
+        let Some(syntax) = detect_block_syntax(input, &tag) else {
+            continue;
+        };
+        let content = &input[tag.end..content_end];
+        if content.trim().is_empty() {
+            continue;
+        }
+        let formatted = format_style_block(content, syntax, options)
+            .map_err(|error| relocate(error, tag.end))?;
+        output.push_str(&input[copied..tag.end]);
+        output.push_str(&formatted);
+        copied = content_end;
+    }
+
+    output.push_str(&input[copied..]);
+    Ok(output)
+}
+
+/// Prefix of identifiers that replace interpolations of template literals.
+const PLACEHOLDER_PREFIX: &str = "malva-placeholder-";
+
+/// Format CSS-in-JS tagged template literal, such as `` css`...` `` of styled-components and emotion.
+///
+/// `quasis` are static chunks of the template literal,
+/// and `expressions` are source code of interpolations between them (without `${` and `}`),
+/// so there must be exactly one more quasi than expressions.
+/// Returned text is the new content between backticks, with interpolations restored.
+///
+/// Templates are formatted as SCSS, since nesting is widely used in CSS-in-JS.
+/// Interpolations can be at positions of selectors, properties, values or whole declarations.
+/// Formatted code keeps the indentation of original code.
+///
+/// Errors point to the location in the template text.
+pub fn format_template_literal(
+    quasis: &[&str],
+    expressions: &[&str],
+    options: &FormatOptions,
+) -> Result<String, Error> {
+    if quasis.len() != expressions.len() + 1 {
+        return Err(Error::MismatchedTemplate {
+            quasis: quasis.len(),
+            expressions: expressions.len(),
+        });
+    }
+
+    let mut code = String::new();
+    let mut template = String::new();
+    let mut placeholders = Vec::with_capacity(expressions.len());
+    for (i, quasi) in quasis.iter().enumerate() {
+        code.push_str(quasi);
+        template.push_str(quasi);
+        let Some(expression) = expressions.get(i) else {
+            break;
+        };
+
+        // Interpolation that takes a whole line is usually a mixin that contains declarations,
+        // so it's replaced by an at-rule instead of an identifier.
+        let next = quasis[i + 1];
+        let is_statement = matches!(code.trim_end().chars().last(), None | Some('{' | '}' | ';'))
+            && next
+                .split('\n')
+                .next()
+                .map(str::trim)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with([';', '}']));
+        let has_semicolon = next.trim_start().starts_with(';');
+        let code_start = code.len();
+        if is_statement {
+            code.push('@');
+        }
+        code.push_str(PLACEHOLDER_PREFIX);
+        code.push_str(&i.to_string());
+        code.push('-');
+        if is_statement && !has_semicolon {
+            code.push(';');
+        }
+        let template_start = template.len();
+        template.push_str("${");
+        template.push_str(expression);
+        template.push('}');
+        placeholders.push(Placeholder {
+            code: code_start..code.len(),
+            template: template_start..template.len(),
+            is_statement,
+            has_semicolon,
+        });
+    }
+    if code.trim().is_empty() {
+        return Ok(template);
+    }
+
+    let line_bounds = LineBounds::new(&template);
+    let map_offset = |pos: usize| match placeholders
+        .iter()
+        .rev()
+        .find(|placeholder| placeholder.code.start <= pos)
+    {
+        Some(placeholder) if pos < placeholder.code.end => placeholder.template.start,
+        Some(placeholder) => pos - placeholder.code.end + placeholder.template.end,
+        None => pos,
+    };
+    let formatted = format_style_block(&code, Syntax::Scss, options)
+        .map_err(|error| relocate_error(error, map_offset, &template, &line_bounds))?;
+
+    let mut output = String::with_capacity(template.len());
+    let mut restored = vec![false; placeholders.len()];
+    let mut rest = &*formatted;
+    while let Some(i) = rest.find(PLACEHOLDER_PREFIX) {
+        let after = &rest[i + PLACEHOLDER_PREFIX.len()..];
+        let digits = after.bytes().take_while(u8::is_ascii_digit).count();
+        let Some((index, placeholder)) = after[..digits]
+            .parse::<usize>()
+            .ok()
+            .filter(|_| after[digits..].starts_with('-'))
+            .and_then(|index| Some((index, placeholders.get(index)?)))
+        else {
+            output.push_str(&rest[..i + PLACEHOLDER_PREFIX.len()]);
+            rest = after;
+            continue;
+        };
+        let mut after = &after[digits + 1..];
+        let before = &rest[..i];
+        if placeholder.is_statement {
+            output.push_str(before.strip_suffix('@').unwrap_or(before));
+            after = after.strip_prefix(';').unwrap_or(after);
+        } else {
+            output.push_str(before);
+        }
+        output.push_str(&template[placeholder.template.clone()]);
+        if placeholder.is_statement && placeholder.has_semicolon {
+            output.push(';');
+        }
+        restored[index] = true;
+        rest = after;
+    }
+    output.push_str(rest);
+
+    if let Some(placeholder) = restored
+        .iter()
+        .zip(&placeholders)
+        .find_map(|(restored, placeholder)| (!restored).then_some(placeholder))
+    {
+        let span = Span {
+            start: placeholder.template.start,
+            end: placeholder.template.end,
+        };
+        let (line, col) = line_bounds.line_col(&template, span.start, ColumnUnit::Utf8);
+        return Err(Error::UnstableOutput { span, line, col });
+    }
+    Ok(output)
+}
+
+struct Placeholder {
+    /// Range of placeholder in code for formatting.
+    code: Range<usize>,
+    /// Range of `${...}` in template text.
+    template: Range<usize>,
+    /// Placeholder is an at-rule that takes place of the whole statement.
+    is_statement: bool,
+    /// Semicolon after statement placeholder is written in the original code.
+    has_semicolon: bool,
+}
+
+/// Format fenced code blocks of CSS, SCSS, Sass and Less in Markdown.
+///
+/// Language of code block is detected by the first word of its info string,
+/// such as `` ```scss `` or `~~~ less title="example"`.
+/// Code blocks in other languages are kept as-is.
+///
+/// Code blocks that can't be parsed are also kept as-is,
+/// and their syntax errors are returned as warnings, which point to the location in the whole document.
+/// Code blocks that fail to be formatted for other reasons, such as unstable output, are kept as-is, too.
+/// Only invalid options stop formatting and are returned as `Err`.
+pub fn format_markdown(
+    input: &str,
+    options: &FormatOptions,
+) -> Result<(String, Vec<Warning>), Error> {
+    crate::validate_options(options)?;
+    let line_bounds = LineBounds::new(input);
+    let mut output = String::with_capacity(input.len());
+    let mut warnings = vec![];
+    let mut copied = 0;
+    let mut lines = input
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, line))
+        })
+        .peekable();
+
+    while let Some((_, line)) = lines.next() {
+        let Some(fence) = parse_fence(line) else {
+            continue;
+        };
+        // code block ends at the closing fence or the end of document
+        let Some(content_start) = lines.peek().map(|(start, _)| *start) else {
+            break;
+        };
+        let Some(content_end) = lines
+            .by_ref()
+            .find(|(_, line)| fence.is_closed_by(line))
+            .map(|(start, _)| start)
+        else {
+            break;
+        };
+        let Some(syntax) = fence.syntax else {
+            continue;
+        };
+        let content = &input[content_start..content_end];
+        if content.trim().is_empty() {
+            continue;
+        }
+
+        // offsets of each line in stripped code and in the whole document
+        let mut line_starts = vec![];
+        let mut code = String::with_capacity(content.len());
+        let mut offset = content_start;
+        for line in content.split_inclusive('\n') {
+            let stripped = strip_indent(line, fence.indent);
+            line_starts.push((code.len(), offset + line.len() - stripped.len()));
+            code.push_str(stripped);
+            offset += line.len();
+        }
+        match format_text(&code, syntax, options) {
+            Ok(formatted) => {
+                output.push_str(&input[copied..content_start]);
+                for line in formatted.split_inclusive('\n') {
+                    if !line.trim().is_empty() {
+                        output.extend(iter::repeat_n(' ', fence.indent));
+                    }
+                    output.push_str(line);
+                }
+                copied = content_end;
+            }
+            Err(Error::Parser(mut error, ..)) => {
+                let map_offset = |pos: usize| {
+                    let index = line_starts.partition_point(|(start, _)| *start <= pos) - 1;
+                    let (code_start, input_start) = line_starts[index];
+                    input_start + pos - code_start
+                };
+                error.span = Span {
+                    start: map_offset(error.span.start),
+                    end: map_offset(error.span.end),
+                };
+                let (line, col) = line_bounds.line_col(input, error.span.start, ColumnUnit::Utf8);
+                warnings.push(Warning {
+                    kind: WarningKind::Parser(error.kind),
+                    span: error.span,
+                    line,
+                    col,
+                });
+            }
+            // code block is kept as-is like other code blocks that can't be formatted
+            Err(_) => {}
+        }
+    }
+
+    output.push_str(&input[copied..]);
+    Ok((output, warnings))
+}
+
+struct Fence {
+    /// Spaces before fence.
+    indent: usize,
+    char: char,
+    len: usize,
+    syntax: Option<Syntax>,
+}
+
+impl Fence {
+    fn is_closed_by(&self, line: &str) -> bool {
+        let trimmed = line.trim_start_matches(' ');
+        let len = trimmed.chars().take_while(|c| *c == self.char).count();
+        line.len() - trimmed.len() <= MAX_FENCE_INDENT
+            && len >= self.len
+            && trimmed[len..].trim().is_empty()
+    }
+}
+
+/// Fences indented with more spaces are parsed as indented code blocks.
+const MAX_FENCE_INDENT: usize = 3;
+
+/// Parse opening fence of code block, such as `` ```scss ``.
+fn parse_fence(line: &str) -> Option<Fence> {
+    let trimmed = line.trim_start_matches(' ');
+    let indent = line.len() - trimmed.len();
+    if indent > MAX_FENCE_INDENT {
+        return None;
+    }
+    let char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|c| *c == char).count();
+    if len < 3 {
+        return None;
+    }
+    let info = trimmed[len..].trim();
+    // backticks can't appear in info string of backtick fence
+    if char == '`' && info.contains('`') {
+        return None;
+    }
+    let lang = info
+        .split(|c: char| c.is_ascii_whitespace() || c == '{')
+        .next()
+        .unwrap_or_default();
+    let syntax = if lang.eq_ignore_ascii_case("css") {
+        Some(Syntax::Css)
+    } else if lang.eq_ignore_ascii_case("scss") {
+        Some(Syntax::Scss)
+    } else if lang.eq_ignore_ascii_case("sass") {
+        Some(Syntax::Sass)
+    } else if lang.eq_ignore_ascii_case("less") {
+        Some(Syntax::Less)
+    } else {
+        None
+    };
+    Some(Fence {
+        indent,
+        char,
+        len,
+        syntax,
+    })
+}
+
+/// Remove spaces of fence indentation at most.
+fn strip_indent(line: &str, indent: usize) -> &str {
+    let spaces = line.bytes().take(indent).take_while(|b| *b == b' ').count();
+    &line[spaces..]
+}
+
+fn format_style_block(
+    content: &str,
+    syntax: Syntax,
+    options: &FormatOptions,
+) -> Result<String, Error> {
+    // Code in the same line of `<style>` tag doesn't have indentation.
+    let base_indent = content
+        .split('\n')
+        .skip(1)
+        .find(|line| !line.trim().is_empty())
+        .filter(|_| content[..content.find('\n').unwrap_or(0)].trim().is_empty())
+        .map_or("", |line| {
+            &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+        });
+    let mut options = options.clone();
+    let indent_width = base_indent
+        .chars()
+        .map(|c| {
+            if c == '\t' {
+                options.layout.indent_width
+            } else {
+                1
+            }
+        })
+        .sum::<usize>();
+    options.layout.print_width = options.layout.print_width.saturating_sub(indent_width);
+    let formatted = format_text(content, syntax, &options)?;
+
+    let line_break = match options.layout.line_break {
+        LineBreak::Lf => "\n",
+        LineBreak::Crlf => "\r\n",
+    };
+    // indentation before closing tag
+    let closing_indent = content
+        .rsplit_once('\n')
+        .map(|(_, last)| last)
+        .filter(|last| last.trim().is_empty())
+        .unwrap_or_default();
+
+    let mut output = String::with_capacity(formatted.len() + content.len());
+    output.push_str(line_break);
+    for line in formatted.split_inclusive('\n') {
+        if !line.trim().is_empty() {
+            output.push_str(base_indent);
+        }
+        output.push_str(line);
+    }
+    output.push_str(closing_indent);
+    Ok(output)
+}
+
+fn format_style_attr(value: &str, quote: char, options: &FormatOptions) -> Result<String, Error> {
+    let mut options = options.clone();
+    options.language.single_line_top_level_declarations = true;
+    // strings in CSS can't use the same quote of attribute
+    let quotes = if quote == '"' {
+        Quotes::AlwaysSingle
+    } else {
+        Quotes::AlwaysDouble
+    };
+    options.language.quotes = quotes;
+    options.language.attr_selector_quotes = Some(quotes);
+    format_text(value, Syntax::Css, &options).map(|formatted| formatted.trim_end().to_owned())
+}
+
+fn detect_block_syntax(input: &str, tag: &Tag) -> Option<Syntax> {
+    let value = |name: &str| {
+        tag.attrs.iter().find_map(|attr| {
+            input[attr.name.clone()]
+                .eq_ignore_ascii_case(name)
+                .then(|| {
+                    attr.value
+                        .as_ref()
+                        .map_or("", |value| &input[value.range.clone()])
+                        .trim()
+                        .to_ascii_lowercase()
+                })
+        })
+    };
+    if let Some(lang) = value("lang") {
+        match &*lang {
+            "css" | "postcss" | "pcss" => Some(Syntax::Css),
+            "scss" => Some(Syntax::Scss),
+            "sass" => Some(Syntax::Sass),
+            "less" => Some(Syntax::Less),
+            _ => None,
+        }
+    } else if let Some(ty) = value("type") {
+        match &*ty {
+            "" | "text/css" => Some(Syntax::Css),
+            "text/scss" | "text/x-scss" => Some(Syntax::Scss),
+            "text/sass" | "text/x-sass" => Some(Syntax::Sass),
+            "text/less" | "text/x-less" => Some(Syntax::Less),
+            _ => None,
+        }
+    } else {
+        Some(Syntax::Css)
+    }
+}
+
+/// Convert error location in a part of file, such as embedded code, to location in the whole file.
+pub(crate) fn relocate_error(
+    error: Error,
+    map_offset: impl Fn(usize) -> usize,
+    input: &str,
+    line_bounds: &LineBounds,
+) -> Error {
+    let relocate = |span: Span| {
+        let span = Span {
+            start: map_offset(span.start),
+            end: map_offset(span.end),
+        };
+        let (line, col) = line_bounds.line_col(input, span.start, ColumnUnit::Utf8);
+        (span, line, col)
+    };
+    match error {
+        Error::Parser(mut error, ..) => {
+            let (span, line, col) = relocate(error.span);
+            error.span = span;
+            Error::Parser(error, line, col)
+        }
+        Error::UnstableOutput { span, .. } => {
+            let (span, line, col) = relocate(span);
+            Error::UnstableOutput { span, line, col }
+        }
+        Error::LostComment { span, .. } => {
+            let (span, line, col) = relocate(span);
+            Error::LostComment { span, line, col }
+        }
+        error => error,
+    }
+}
+
+struct Tag {
+    name: Range<usize>,
+    attrs: Vec<Attr>,
+    self_closing: bool,
+    /// Offset after `>`.
+    end: usize,
+}
+
+struct Attr {
+    name: Range<usize>,
+    value: Option<AttrValue>,
+}
+
+struct AttrValue {
+    /// Range without quotes.
+    range: Range<usize>,
+    quote: Option<char>,
+}
+
+/// Parse start tag at the given offset of `<`.
+fn parse_tag(input: &str, start: usize) -> Option<Tag> {
+    let bytes = input.as_bytes();
+    let mut pos = start + 1;
+    if !bytes.get(pos)?.is_ascii_alphabetic() {
+        return None;
+    }
+    let name_start = pos;
+    while bytes
+        .get(pos)
+        .is_some_and(|b| !b.is_ascii_whitespace() && *b != b'>' && *b != b'/')
+    {
+        pos += 1;
+    }
+    let name = name_start..pos;
+
+    let mut attrs = vec![];
+    loop {
+        while bytes.get(pos)?.is_ascii_whitespace() {
+            pos += 1;
+        }
+        match bytes.get(pos)? {
+            b'>' => {
+                return Some(Tag {
+                    name,
+                    attrs,
+                    self_closing: false,
+                    end: pos + 1,
+                });
+            }
+            b'/' if bytes.get(pos + 1) == Some(&b'>') => {
+                return Some(Tag {
+                    name,
+                    attrs,
+                    self_closing: true,
+                    end: pos + 2,
+                });
+            }
+            _ => {}
+        }
+
+        let attr_start = pos;
+        // attribute like `{...props}` in Svelte and Astro
+        if bytes[pos] == b'{' {
+            pos = skip_braces(bytes, pos)?;
+            continue;
+        }
+        while bytes.get(pos).is_some_and(|b| {
+            !(b.is_ascii_whitespace()
+                || matches!(b, b'=' | b'>')
+                || *b == b'/' && bytes.get(pos + 1) == Some(&b'>'))
+        }) {
+            pos += 1;
+        }
+        if pos == attr_start {
+            // stray `/` or `=`
+            pos += 1;
+            continue;
+        }
+        let name = attr_start..pos;
+
+        let mut lookahead = pos;
+        while bytes.get(lookahead).is_some_and(u8::is_ascii_whitespace) {
+            lookahead += 1;
+        }
+        if bytes.get(lookahead) != Some(&b'=') {
+            attrs.push(Attr { name, value: None });
+            continue;
+        }
+        pos = lookahead + 1;
+        while bytes.get(pos)?.is_ascii_whitespace() {
+            pos += 1;
+        }
+        let value = match bytes[pos] {
+            quote @ (b'"' | b'\'') => {
+                let value_start = pos + 1;
+                let value_end = input[value_start..].find(quote as char)? + value_start;
+                pos = value_end + 1;
+                AttrValue {
+                    range: value_start..value_end,
+                    quote: Some(quote as char),
+                }
+            }
+            b'{' => {
+                let value_start = pos;
+                pos = skip_braces(bytes, pos)?;
+                AttrValue {
+                    range: value_start..pos,
+                    quote: None,
+                }
+            }
+            _ => {
+                let value_start = pos;
+                while bytes
+                    .get(pos)
+                    .is_some_and(|b| !b.is_ascii_whitespace() && *b != b'>')
+                {
+                    pos += 1;
+                }
+                AttrValue {
+                    range: value_start..pos,
+                    quote: None,
+                }
+            }
+        };
+        attrs.push(Attr {
+            name,
+            value: Some(value),
+        });
+    }
+}
+
+/// Return offset after matched `}`.
+fn skip_braces(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut pos = start;
+    loop {
+        match bytes.get(pos)? {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos + 1);
+                }
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+}
+
+/// Return offset of closing tag, or the end of input if there's no closing tag.
+fn find_closing_tag(input: &str, start: usize, name: &str) -> usize {
+    let bytes = input.as_bytes();
+    let mut pos = start;
+    while let Some(i) = input[pos..].find("</") {
+        let tag_start = pos + i;
+        let name_end = tag_start + 2 + name.len();
+        if input
+            .get(tag_start + 2..name_end)
+            .is_some_and(|s| s.eq_ignore_ascii_case(name))
+            && bytes
+                .get(name_end)
+                .is_none_or(|b| b.is_ascii_whitespace() || *b == b'>')
+        {
+            return tag_start;
+        }
+        pos = tag_start + 2;
+    }
+    input.len()
+}
@@ -1,13 +1,101 @@
-use std::fmt::Display;
+use crate::{ColumnUnit, LineBounds};
+use raffia::{Span, Syntax};
+use std::fmt::{Display, Write};
 
 /// The error type for Malva.
+///
+/// More variants may be added in the future,
+/// so matching it requires a wildcard arm.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// Error from the parser, usually related to syntax error.
     /// The first component is the error type from Raffia,
     /// and the second component is error line number,
-    /// and the third component is error column number.
+    /// and the third component is error column number in UTF-8 bytes.
     Parser(raffia::error::Error, usize, usize),
+
+    /// Some options are invalid, such as zero indent width.
+    /// The component is the description of the invalid option.
+    InvalidOptions(String),
+
+    /// Failed to access file system, such as reading config files.
+    /// The component is the description of the failure.
+    Io(String),
+
+    /// Formatted code is different from the original code in semantics,
+    /// or formatting it again produces different result.
+    /// The span points to the original code where the difference starts.
+    UnstableOutput { span: Span, line: usize, col: usize },
+
+    /// Comment in the original code isn't printed.
+    /// The span is the location of that comment.
+    LostComment { span: Span, line: usize, col: usize },
+
+    /// Syntax tree can't be inspected in verify mode.
+    /// The component is the description of the failure.
+    Verify(String),
+
+    /// Converting code between the given syntaxes isn't supported,
+    /// or converted code can't be parsed in the target syntax.
+    UnsupportedConversion { from: Syntax, to: Syntax },
+
+    /// Template literal doesn't have exactly one more quasi than expressions.
+    /// Components are the numbers of quasis and expressions.
+    MismatchedTemplate { quasis: usize, expressions: usize },
+}
+
+impl Error {
+    /// Location of the problem in the original code, if any.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Error::Parser(error, ..) => Some(error.span.clone()),
+            Error::InvalidOptions(..)
+            | Error::Io(..)
+            | Error::Verify(..)
+            | Error::UnsupportedConversion { .. }
+            | Error::MismatchedTemplate { .. } => None,
+            Error::UnstableOutput { span, .. } | Error::LostComment { span, .. } => {
+                Some(span.clone())
+            }
+        }
+    }
+
+    /// Line number and column number of the problem in the original code, if any.
+    ///
+    /// Line numbers and column numbers carried by variants are counted in UTF-8 bytes,
+    /// while this converts them in the given unit.
+    pub fn line_col(&self, source: &str, unit: ColumnUnit) -> Option<(usize, usize)> {
+        self.span()
+            .map(|span| LineBounds::new(source).line_col(source, span.start, unit))
+    }
+
+    /// Render error message with a code frame that
+    /// shows the source line and a caret pointing to the problem.
+    pub fn render(&self, source: &str) -> String {
+        let mut output = format!("error: {self}\n");
+        let Some(span) = self.span() else {
+            return output;
+        };
+        let start = span.start.min(source.len());
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |i| start + i);
+        let line = source[line_start..line_end].trim_end_matches('\r');
+        let line_number = (source[..line_start].matches('\n').count() + 1).to_string();
+        let gutter = " ".repeat(line_number.len());
+        let col = source[line_start..start].chars().count();
+        let len = source[start..span.end.clamp(start, line_start + line.len())]
+            .chars()
+            .count()
+            .max(1);
+
+        let _ = writeln!(output, "{gutter} |");
+        let _ = writeln!(output, "{line_number} | {line}");
+        let _ = writeln!(output, "{gutter} | {}{}", " ".repeat(col), "^".repeat(len));
+        output
+    }
 }
 
 impl Display for Error {
@@ -16,8 +104,73 @@ impl Display for Error {
             Error::Parser(error, line, col) => {
                 write!(f, "syntax error at line {line}, col {col}: {}", error.kind)
             }
+            Error::InvalidOptions(message) => write!(f, "invalid options: {message}"),
+            Error::Io(message) => write!(f, "io error: {message}"),
+            Error::UnstableOutput { line, col, .. } => {
+                write!(f, "unstable output at line {line}, col {col}")
+            }
+            Error::LostComment { line, col, .. } => {
+                write!(f, "comment at line {line}, col {col} is lost")
+            }
+            Error::Verify(message) => write!(f, "failed to verify output: {message}"),
+            Error::UnsupportedConversion { from, to } => {
+                write!(f, "can't convert from {from:?} to {to:?}")
+            }
+            Error::MismatchedTemplate {
+                quasis,
+                expressions,
+            } => write!(
+                f,
+                "template literal has {quasis} quasis, but {expressions} expressions"
+            ),
         }
     }
 }
 
 impl std::error::Error for Error {}
+
+/// Problem that doesn't prevent formatting, but usually indicates invalid code.
+#[derive(Clone, Debug)]
+pub struct Warning {
+    /// Type of the warning.
+    pub kind: WarningKind,
+    /// Location of the warning in the original code.
+    pub span: Span,
+    /// Line number of the warning.
+    pub line: usize,
+    /// Column number of the warning in UTF-8 bytes.
+    pub col: usize,
+}
+
+impl Warning {
+    /// Line number and column number of the warning in the given unit.
+    /// See [`Error::line_col`].
+    pub fn line_col(&self, source: &str, unit: ColumnUnit) -> (usize, usize) {
+        LineBounds::new(source).line_col(source, self.span.start, unit)
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "warning at line {}, col {}: ", self.line, self.col)?;
+        match &self.kind {
+            WarningKind::Parser(kind) => write!(f, "{kind}"),
+            WarningKind::InvalidDirective(message) => write!(f, "invalid directive: {message}"),
+        }
+    }
+}
+
+/// The type of [`Warning`].
+///
+/// More variants may be added in the future,
+/// so matching it requires a wildcard arm.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// Recoverable error from the parser, such as top-level declarations in CSS.
+    Parser(raffia::error::ErrorKind),
+
+    /// Comment directive that overrides options can't be applied, such as malformed options.
+    /// The component is the description of the problem.
+    InvalidDirective(String),
+}
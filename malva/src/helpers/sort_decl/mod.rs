@@ -1,6 +1,7 @@
-pub use concentric::compare_in_concentric;
-pub use smacss::compare_in_smacss;
-use std::{cmp::Ordering, ops::ControlFlow};
+use crate::config::UnspecifiedProperties;
+pub use concentric::{compare_in_concentric, group_in_concentric};
+pub use smacss::{compare_in_smacss, group_in_smacss};
+use std::cmp::Ordering;
 
 mod concentric;
 mod smacss;
@@ -13,6 +14,29 @@ pub fn compare_in_alphabetical(a: &str, b: &str) -> Ordering {
     }
 }
 
+pub fn compare_in_custom(
+    a: &str,
+    b: &str,
+    list: &[String],
+    unspecified: &UnspecifiedProperties,
+) -> Ordering {
+    match find_list_indexes(a, b, list) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => match unspecified {
+            UnspecifiedProperties::First => Ordering::Greater,
+            UnspecifiedProperties::Last | UnspecifiedProperties::Alphabetical => Ordering::Less,
+        },
+        (None, Some(_)) => match unspecified {
+            UnspecifiedProperties::First => Ordering::Less,
+            UnspecifiedProperties::Last | UnspecifiedProperties::Alphabetical => Ordering::Greater,
+        },
+        (None, None) => match unspecified {
+            UnspecifiedProperties::Alphabetical => compare_in_alphabetical(a, b),
+            UnspecifiedProperties::First | UnspecifiedProperties::Last => Ordering::Equal,
+        },
+    }
+}
+
 fn strip_vendor_prefix(s: &str) -> &str {
     s.strip_prefix('-')
         .and_then(|s| {
@@ -23,31 +47,63 @@ fn strip_vendor_prefix(s: &str) -> &str {
         .unwrap_or(s)
 }
 
-fn compare_by_list_index<const N: usize>(a: &str, b: &str, list: [&'static str; N]) -> Ordering {
-    let a = strip_vendor_prefix(a);
-    let b = strip_vendor_prefix(b);
-
-    let result = list
-        .iter()
-        .enumerate()
-        .try_fold((None, None), |mut result, (index, property)| {
-            if result.0.is_none() && property.eq_ignore_ascii_case(a) {
-                result.0 = Some(index)
-            }
-            if result.1.is_none() && property.eq_ignore_ascii_case(b) {
-                result.1 = Some(index)
-            }
-            if result.0.is_some() && result.1.is_some() {
-                ControlFlow::Break(result)
-            } else {
-                ControlFlow::Continue(result)
-            }
-        });
-    if let ControlFlow::Break((Some(a), Some(b))) | ControlFlow::Continue((Some(a), Some(b))) =
-        result
-    {
+fn compare_by_list_index(
+    a: &str,
+    b: &str,
+    list: impl IntoIterator<Item = impl AsRef<str>> + Clone,
+) -> Ordering {
+    if let (Some(a), Some(b)) = find_list_indexes(a, b, list) {
         a.cmp(&b)
     } else {
         Ordering::Equal
     }
 }
+
+/// Find indexes of the first entries in list that match the given properties.
+/// Entries that end with `*` match properties with that prefix, such as `margin-*`.
+fn find_list_indexes(
+    a: &str,
+    b: &str,
+    list: impl IntoIterator<Item = impl AsRef<str>> + Clone,
+) -> (Option<usize>, Option<usize>) {
+    (find_list_index(a, list.clone()), find_list_index(b, list))
+}
+
+/// Properties with vendor prefix are matched by their full names first,
+/// then by their unprefixed names.
+fn find_list_index(
+    property: &str,
+    list: impl IntoIterator<Item = impl AsRef<str>> + Clone,
+) -> Option<usize> {
+    let position = |property| {
+        list.clone()
+            .into_iter()
+            .position(|pattern| is_property_matched(pattern.as_ref(), property))
+    };
+    position(property).or_else(|| {
+        let unprefixed = strip_vendor_prefix(property);
+        if unprefixed.len() < property.len() {
+            position(unprefixed)
+        } else {
+            None
+        }
+    })
+}
+
+/// Find index of the group that contains the given property.
+fn find_group_index(property: &str, groups: &[&[&str]]) -> Option<usize> {
+    let property = strip_vendor_prefix(property);
+    groups
+        .iter()
+        .position(|group| group.iter().any(|p| p.eq_ignore_ascii_case(property)))
+}
+
+fn is_property_matched(pattern: &str, property: &str) -> bool {
+    if let Some(prefix) = pattern.strip_suffix('*') {
+        property
+            .get(..prefix.len())
+            .is_some_and(|s| s.eq_ignore_ascii_case(prefix))
+    } else {
+        pattern.eq_ignore_ascii_case(property)
+    }
+}
@@ -1,40 +1,558 @@
 #![doc = include_str!("../README.md")]
 
 pub mod config;
+#[cfg(feature = "config_file")]
+pub mod config_file;
+mod convert;
 mod ctx;
 mod doc_gen;
+mod edit;
+#[cfg(feature = "config_file")]
+pub mod editorconfig;
+pub mod embedded;
 mod error;
 mod helpers;
 mod line_bounds;
+mod range;
 mod state;
+mod tolerant;
+#[cfg(feature = "verify")]
+mod verify;
 
-use crate::{config::FormatOptions, ctx::Ctx, doc_gen::DocGen, state::State};
-pub use crate::{error::Error, line_bounds::LineBounds};
+use crate::{
+    config::{FormatOptions, PartialFormatOptions},
+    ctx::{Ctx, PrintSegments},
+    doc_gen::DocGen,
+    state::State,
+};
+pub use crate::{
+    edit::TextEdit,
+    error::{Error, Warning, WarningKind},
+    line_bounds::{ColumnUnit, LineBounds},
+};
 pub use raffia::Syntax;
-use raffia::{ParserBuilder, ParserOptions, ast::Stylesheet, token::Comment};
-use std::path::Path;
+use raffia::{ParserBuilder, ParserOptions, Span, Spanned, ast::Stylesheet, token::Comment};
+use std::{borrow::Cow, ops::Range, path::Path};
 
 /// Format the given source code.
 pub fn format_text(input: &str, syntax: Syntax, options: &FormatOptions) -> Result<String, Error> {
+    let mut comments = vec![];
+    let stylesheet = parse_stylesheet(input, syntax, &mut comments)?;
+    match format_stylesheet(&stylesheet, &comments, input, syntax, options)? {
+        Some(output) => {
+            verify_output(input, &stylesheet, &output, syntax, options).map(|()| output)
+        }
+        None => Ok(input.to_owned()),
+    }
+}
+
+/// Format the given source code, and return warnings alongside formatted code.
+///
+/// Warnings come from recoverable errors of the parser,
+/// such as top-level declarations in CSS,
+/// and from comment directives that can't be applied.
+/// They don't stop formatting, but usually indicate suspicious code.
+pub fn format_text_with_diagnostics(
+    input: &str,
+    syntax: Syntax,
+    options: &FormatOptions,
+) -> Result<(String, Vec<Warning>), Error> {
+    let resolved = resolve_options(options, syntax)?;
+    let options = resolved.as_deref().unwrap_or(options);
+    let line_bounds = LineBounds::new(input);
+    let mut comments = vec![];
+    let (stylesheet, recoverable_errors) =
+        parse_with_recoverable_errors(input, syntax, &mut comments).map_err(|error| {
+            let (line, col) = line_bounds.line_col(input, error.span.start, ColumnUnit::Utf8);
+            Error::Parser(error, line, col)
+        })?;
+    let mut warnings = recoverable_errors
+        .into_iter()
+        .map(|error| {
+            let (line, col) = line_bounds.line_col(input, error.span.start, ColumnUnit::Utf8);
+            Warning {
+                kind: WarningKind::Parser(error.kind),
+                span: error.span,
+                line,
+                col,
+            }
+        })
+        .collect::<Vec<_>>();
+    let overridden = apply_config_comment(&comments, options, &mut warnings)?;
+    let options = overridden.as_deref().unwrap_or(options);
+
+    if is_file_ignored(&comments, options) {
+        Ok((input.to_owned(), warnings))
+    } else {
+        let (output, directive_warnings) =
+            print_stylesheet_checked(&stylesheet, &comments, input, line_bounds, syntax, options)?;
+        verify_output(input, &stylesheet, &output, syntax, options)?;
+        warnings.extend(directive_warnings);
+        warnings.sort_by_key(|warning| warning.span.start);
+        Ok((output, warnings))
+    }
+}
+
+/// Format statements that intersect with the given byte range,
+/// while leaving the rest of source code untouched.
+///
+/// Statements are searched at any nesting depth:
+/// if the range is completely inside a block, only statements of that block will be formatted,
+/// and the indentation of that block will be preserved.
+///
+/// In verify mode, only lost comments are checked, since the rest of source code isn't formatted.
+pub fn format_range(
+    input: &str,
+    syntax: Syntax,
+    options: &FormatOptions,
+    range: Range<usize>,
+) -> Result<String, Error> {
+    let resolved = resolve_options(options, syntax)?;
+    let options = resolved.as_deref().unwrap_or(options);
     let line_bounds = LineBounds::new(input);
     let mut comments = vec![];
-    let mut parser = ParserBuilder::new(input)
+    let stylesheet = parse_stylesheet(input, syntax, &mut comments)?;
+    let overridden = apply_config_comment(&comments, options, &mut vec![])?;
+    let options = overridden.as_deref().unwrap_or(options);
+
+    if is_file_ignored(&comments, options) {
+        return Ok(input.to_owned());
+    }
+
+    let printed_comments = Default::default();
+    let warnings = Default::default();
+    let print_segments = Default::default();
+    let ctx = Ctx {
+        source: Some(input),
+        syntax,
+        options: &options.language,
+        comments: &comments,
+        indent_width: options.layout.indent_width,
+        line_bounds: &line_bounds,
+        printed_comments: &printed_comments,
+        initial_options: options,
+        warnings: &warnings,
+        print_segments: &print_segments,
+    };
+    let (statements, state) =
+        range::find_statements(&stylesheet.statements, &range, 0, &ctx, default_state());
+    let (Some(first), Some(last)) = (statements.first(), statements.last()) else {
+        return Ok(input.to_owned());
+    };
+    let start = first.span().start;
+    let end = range::end_with_semicolon(input, last.span().end);
+
+    // Text before the first statement at the same line is printed together,
+    // so the printer knows the actual column when checking print width.
+    let line_start = input[..start].rfind('\n').map_or(0, |i| i + 1);
+    let prefix = &input[line_start..start];
+    let indent = prefix
+        .chars()
+        .take_while(|c| c.is_ascii_whitespace())
+        .map(|c| {
+            if c == '\t' {
+                options.layout.indent_width
+            } else {
+                1
+            }
+        })
+        .sum();
+
+    // statements may be affected by options override directives in outer blocks
+    let state = State { indent, ..state };
+    let scoped_options = state.options_override.clone();
+    let scoped_ctx = scoped_options
+        .as_deref()
+        .map(|options| ctx.with_options(options));
+    print_segments.borrow_mut().options = state.options_override.clone();
+    let mut docs = vec![];
+    doc_gen::format_statements(
+        &mut docs,
+        statements,
+        &Span {
+            start,
+            end: last.span().end,
+        },
+        tiny_pretty::Doc::hard_line(),
+        scoped_ctx.as_ref().unwrap_or(&ctx),
+        &state,
+    );
+    check_comments(
+        &ctx,
+        input,
+        &Span {
+            start,
+            end: last.span().end,
+        },
+    )?;
+
+    let formatted = print_segments
+        .into_inner()
+        .with_last(tiny_pretty::Doc::list(docs))
+        .enumerate()
+        .fold(
+            String::new(),
+            |mut formatted, (i, (doc, segment_options))| {
+                let doc = doc.nest(indent);
+                let doc = if i == 0 {
+                    tiny_pretty::Doc::text(prefix).append(doc)
+                } else {
+                    doc
+                };
+                formatted.push_str(&tiny_pretty::print(
+                    &doc,
+                    &print_options(segment_options.as_deref().unwrap_or(options)),
+                ));
+                formatted
+            },
+        );
+
+    let mut output = String::with_capacity(input.len());
+    output.push_str(&input[..start]);
+    output.push_str(&formatted[prefix.len()..]);
+    output.push_str(&input[end..]);
+    Ok(output)
+}
+
+/// Format the given source code, and return text edits
+/// instead of the whole formatted code.
+///
+/// Edits are computed by comparing statements of original code and formatted code,
+/// so they only cover the changed parts. Edits are sorted and never overlap.
+pub fn format_text_edits(
+    input: &str,
+    syntax: Syntax,
+    options: &FormatOptions,
+) -> Result<Vec<TextEdit>, Error> {
+    let mut comments = vec![];
+    let stylesheet = parse_stylesheet(input, syntax, &mut comments)?;
+    match format_stylesheet(&stylesheet, &comments, input, syntax, options)? {
+        Some(output) => {
+            verify_output(input, &stylesheet, &output, syntax, options)?;
+            Ok(collect_edits(input, &stylesheet, &output, syntax))
+        }
+        None => Ok(vec![]),
+    }
+}
+
+/// Format the given source code with cursor position,
+/// and return formatted code with new cursor position.
+///
+/// Both cursor positions are byte offsets. Cursor inside a multi-byte character
+/// will be moved to the start of that character.
+/// This is useful for editors to keep cursor at the same place after formatting.
+///
+/// Cursor is mapped through text edits like [`format_text_edits`], rather than positions of syntax nodes.
+/// Cursor outside edits moves along with the surrounding code,
+/// and cursor inside an edit is placed after the same number of non-whitespace characters.
+/// The result is exact when the edit only changes whitespaces,
+/// but it's only approximate when characters are added, removed or reordered in the same edit,
+/// such as sorting declarations.
+pub fn format_text_with_cursor(
+    input: &str,
+    syntax: Syntax,
+    options: &FormatOptions,
+    cursor: usize,
+) -> Result<(String, usize), Error> {
+    let mut comments = vec![];
+    let stylesheet = parse_stylesheet(input, syntax, &mut comments)?;
+    let Some(output) = format_stylesheet(&stylesheet, &comments, input, syntax, options)? else {
+        return Ok((input.to_owned(), edit::floor_char_boundary(input, cursor)));
+    };
+    verify_output(input, &stylesheet, &output, syntax, options)?;
+    let edits = collect_edits(input, &stylesheet, &output, syntax);
+    let cursor = edit::map_offset(input, &edits, cursor);
+    Ok((output, cursor))
+}
+
+/// Format the given source code while tolerating syntax errors.
+///
+/// Top-level statements that can be parsed will be formatted,
+/// while top-level statements that contain syntax errors will be kept as-is.
+/// Errors of those unparseable statements are returned alongside formatted code.
+///
+/// Only syntax errors are tolerated. If formatting fails for other reasons,
+/// such as invalid options or verify failure, source code will be returned as-is with that error.
+/// In verify mode, each group of parseable statements is verified separately,
+/// and it's kept as-is when verify fails.
+pub fn format_text_tolerant(
+    input: &str,
+    syntax: Syntax,
+    options: &FormatOptions,
+) -> (String, Vec<Error>) {
+    let resolved = match resolve_options(options, syntax) {
+        Ok(resolved) => resolved,
+        Err(error) => return (input.to_owned(), vec![error]),
+    };
+    match format_text(input, syntax, options) {
+        Ok(output) => (output, vec![]),
+        Err(Error::Parser(..)) => {
+            tolerant::format(input, syntax, resolved.as_deref().unwrap_or(options))
+        }
+        Err(error) => (input.to_owned(), vec![error]),
+    }
+}
+
+/// Convert source code between indented Sass syntax and SCSS syntax,
+/// and format it at the same time.
+///
+/// Converting to the same syntax is the same as [`format_text`].
+/// Other conversions, such as converting between SCSS and Less, aren't supported.
+///
+/// Statements in indented Sass can't span multiple lines,
+/// so print width is ignored when converting to indented Sass.
+/// In verify mode, only lost comments are checked,
+/// since syntax trees of different syntaxes can't be compared.
+/// If converted code still can't be parsed as indented Sass,
+/// [`Error::UnsupportedConversion`] will be returned.
+pub fn convert_syntax(
+    input: &str,
+    from: Syntax,
+    to: Syntax,
+    options: &FormatOptions,
+) -> Result<String, Error> {
+    if from == to {
+        return format_text(input, to, options);
+    }
+    if !matches!(
+        (&from, &to),
+        (Syntax::Sass, Syntax::Scss) | (Syntax::Scss, Syntax::Sass)
+    ) {
+        return Err(Error::UnsupportedConversion { from, to });
+    }
+    let resolved = resolve_options(options, to)?;
+    let options = resolved.as_deref().unwrap_or(options);
+    // offsets are kept after joining lines, so line bounds of original code are used
+    let line_bounds = LineBounds::new(input);
+    let joined;
+    let input = if from == Syntax::Sass {
+        let mut comments = vec![];
+        let stylesheet = parse_stylesheet(input, from, &mut comments)?;
+        joined = convert::join_sass_selector_lines(input, &stylesheet);
+        &*joined
+    } else {
+        input
+    };
+    let mut comments = vec![];
+    let stylesheet = parse_stylesheet(input, from, &mut comments)?;
+    let overridden = apply_config_comment(&comments, options, &mut vec![])?;
+    let options = overridden.as_deref().unwrap_or(options);
+    if to == Syntax::Sass {
+        let mut options = options.clone();
+        options.layout.print_width = usize::MAX;
+        let (output, _) =
+            print_stylesheet_checked(&stylesheet, &comments, input, line_bounds, to, &options)?;
+        // line breaks from original code may still be kept
+        if parse(&output, to, &mut vec![]).is_err() {
+            return Err(Error::UnsupportedConversion { from, to });
+        }
+        Ok(output)
+    } else {
+        print_stylesheet_checked(&stylesheet, &comments, input, line_bounds, to, options)
+            .map(|(output, _)| output)
+    }
+}
+
+fn collect_edits(
+    input: &str,
+    stylesheet: &Stylesheet,
+    output: &str,
+    syntax: Syntax,
+) -> Vec<TextEdit> {
+    let mut collector = edit::EditsCollector {
+        old: input,
+        new: output,
+        edits: vec![],
+    };
+    let mut output_comments = vec![];
+    if let Ok(output_stylesheet) = parse(output, syntax, &mut output_comments) {
+        collector.diff_statements(
+            &stylesheet.statements,
+            &Span {
+                start: 0,
+                end: input.len(),
+            },
+            &output_stylesheet.statements,
+            &Span {
+                start: 0,
+                end: output.len(),
+            },
+        );
+    } else {
+        collector.diff_text(0..input.len(), 0..output.len());
+    }
+    collector.edits
+}
+
+fn parse_stylesheet<'s>(
+    input: &'s str,
+    syntax: Syntax,
+    comments: &mut Vec<Comment<'s>>,
+) -> Result<Stylesheet<'s>, Error> {
+    // parser is built here instead of calling `parse` to save a stack frame,
+    // since parsing deeply nested code requires deep recursion
+    build_parser(input, syntax, comments)
+        .parse::<Stylesheet>()
+        .map_err(|error| {
+            let (line, col) =
+                LineBounds::new(input).line_col(input, error.span.start, ColumnUnit::Utf8);
+            Error::Parser(error, line, col)
+        })
+}
+
+/// Print the parsed stylesheet with options resolved for specific syntax
+/// and merged with the configuration comment.
+/// `None` will be returned if the whole file is ignored.
+///
+/// This is separated from public functions, so deeply nested code
+/// won't be parsed with a large stack frame of options held by callers.
+fn format_stylesheet<'s>(
+    stylesheet: &Stylesheet<'s>,
+    comments: &[Comment<'s>],
+    input: &'s str,
+    syntax: Syntax,
+    options: &FormatOptions,
+) -> Result<Option<String>, Error> {
+    let resolved = resolve_options(options, syntax)?;
+    let options = resolved.as_deref().unwrap_or(options);
+    let overridden = apply_config_comment(comments, options, &mut vec![])?;
+    let options = overridden.as_deref().unwrap_or(options);
+    if is_file_ignored(comments, options) {
+        return Ok(None);
+    }
+    let line_bounds = LineBounds::new(input);
+    print_stylesheet_checked(stylesheet, comments, input, line_bounds, syntax, options)
+        .map(|(output, _)| Some(output))
+}
+
+/// Check formatted code in verify mode, which is shared by all formatting functions
+/// that format the whole file.
+#[cfg_attr(not(feature = "verify"), allow(unused_variables))]
+fn verify_output(
+    input: &str,
+    stylesheet: &Stylesheet,
+    output: &str,
+    syntax: Syntax,
+    options: &FormatOptions,
+) -> Result<(), Error> {
+    #[cfg(feature = "verify")]
+    if options.verify {
+        return verify::verify(input, stylesheet, output, syntax, options);
+    }
+    Ok(())
+}
+
+fn parse<'s>(
+    input: &'s str,
+    syntax: Syntax,
+    comments: &mut Vec<Comment<'s>>,
+) -> Result<Stylesheet<'s>, raffia::error::Error> {
+    build_parser(input, syntax, comments).parse::<Stylesheet>()
+}
+
+fn parse_with_recoverable_errors<'s>(
+    input: &'s str,
+    syntax: Syntax,
+    comments: &mut Vec<Comment<'s>>,
+) -> Result<(Stylesheet<'s>, Vec<raffia::error::Error>), raffia::error::Error> {
+    let mut parser = build_parser(input, syntax, comments);
+    let stylesheet = parser.parse::<Stylesheet>()?;
+    Ok((stylesheet, parser.recoverable_errors().to_vec()))
+}
+
+fn build_parser<'cmt, 's: 'cmt>(
+    input: &'s str,
+    syntax: Syntax,
+    comments: &'cmt mut Vec<Comment<'s>>,
+) -> raffia::Parser<'cmt, 's> {
+    ParserBuilder::new(input)
         .syntax(syntax)
-        .comments(&mut comments)
+        .comments(comments)
         .options(ParserOptions {
             try_parsing_value_in_custom_property: true,
             tolerate_semicolon_in_sass: true,
         })
-        .build();
-    let stylesheet = match parser.parse::<Stylesheet>() {
-        Ok(stylesheet) => stylesheet,
+        .build()
+}
+
+/// Resolve options for specific syntax and validate them.
+/// `None` will be returned if there're no overrides for that syntax.
+///
+/// Resolved options are boxed to keep stack frames of callers small,
+/// since parsing deeply nested code requires deep recursion.
+fn resolve_options(
+    options: &FormatOptions,
+    syntax: Syntax,
+) -> Result<Option<Box<FormatOptions>>, Error> {
+    let resolved = match options.resolve(syntax) {
+        Cow::Borrowed(_) => None,
+        Cow::Owned(options) => Some(Box::new(options)),
+    };
+    validate_options(resolved.as_deref().unwrap_or(options))?;
+    Ok(resolved)
+}
+
+fn validate_options(options: &FormatOptions) -> Result<(), Error> {
+    if options.layout.indent_width == 0 {
+        Err(Error::InvalidOptions(
+            "`indentWidth` must be greater than 0".into(),
+        ))
+    } else if options.verify && !cfg!(feature = "verify") {
+        Err(Error::InvalidOptions(
+            "`verify` requires the `verify` feature of Malva".into(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Apply options in the configuration comment at the beginning of file,
+/// which are merged over the given options.
+/// `None` will be returned if there's no configuration comment,
+/// or it can't be parsed without the `config_serde` feature, which is reported as a warning.
+///
+/// Merged options are boxed like [`resolve_options`].
+fn apply_config_comment(
+    comments: &[Comment],
+    options: &FormatOptions,
+    warnings: &mut Vec<Warning>,
+) -> Result<Option<Box<FormatOptions>>, Error> {
+    let Some((comment, json)) = comments
+        .first()
+        .filter(|comment| comment.span.start == 0)
+        .and_then(|comment| {
+            doc_gen::strip_directive(comment, &options.language.config_comment_directive)
+                .map(|json| (comment, json))
+        })
+    else {
+        return Ok(None);
+    };
+    let overrides = match PartialFormatOptions::from_json(json) {
+        Ok(overrides) => overrides,
+        Err(message) if !cfg!(feature = "config_serde") => {
+            warnings.push(Warning {
+                kind: WarningKind::InvalidDirective(message),
+                span: comment.span.clone(),
+                line: 1,
+                col: 1,
+            });
+            return Ok(None);
+        }
         Err(error) => {
-            let (line, col) = line_bounds.get_line_col(error.span.start);
-            return Err(Error::Parser(error, line, col));
+            return Err(Error::InvalidOptions(format!(
+                "invalid options in `{}` comment: {error}",
+                options.language.config_comment_directive
+            )));
         }
     };
+    let mut options = Box::new(options.clone());
+    overrides.apply_to(&mut options);
+    validate_options(&options)?;
+    Ok(Some(options))
+}
 
-    if comments.first().is_some_and(|comment| {
+fn is_file_ignored(comments: &[Comment], options: &FormatOptions) -> bool {
+    comments.first().is_some_and(|comment| {
         comment.span.start == 0
             && comment
                 .content
@@ -43,18 +561,7 @@ pub fn format_text(input: &str, syntax: Syntax, options: &FormatOptions) -> Resu
                 .is_some_and(|rest| {
                     rest.is_empty() || rest.starts_with(|c: char| c.is_ascii_whitespace())
                 })
-    }) {
-        Ok(input.to_owned())
-    } else {
-        Ok(print_stylesheet(
-            &stylesheet,
-            &comments,
-            Some(input),
-            line_bounds,
-            syntax,
-            options,
-        ))
-    }
+    })
 }
 
 /// Print the given stylesheet AST.
@@ -67,34 +574,145 @@ pub fn print_stylesheet<'a, 's>(
     syntax: Syntax,
     options: &'a FormatOptions,
 ) -> String {
-    use tiny_pretty::{IndentKind, PrintOptions};
+    print_resolved(
+        stylesheet,
+        comments,
+        source,
+        line_bounds,
+        syntax,
+        &options.resolve(syntax),
+    )
+}
 
+/// Print the given stylesheet AST like [`print_stylesheet`],
+/// but options have been resolved for specific syntax.
+pub(crate) fn print_resolved<'s>(
+    stylesheet: &Stylesheet<'s>,
+    comments: &[Comment<'s>],
+    source: Option<&'s str>,
+    line_bounds: LineBounds,
+    syntax: Syntax,
+    options: &FormatOptions,
+) -> String {
+    let printed_comments = Default::default();
+    let print_segments = Default::default();
     let ctx = Ctx {
         source,
         syntax,
         options: &options.language,
         comments,
         indent_width: options.layout.indent_width,
-        line_bounds,
+        line_bounds: &line_bounds,
+        printed_comments: &printed_comments,
+        initial_options: options,
+        warnings: &Default::default(),
+        print_segments: &print_segments,
     };
-    let state = State {
+    let doc = stylesheet.doc(&ctx, &default_state());
+    print_segmented(doc, print_segments.into_inner(), options)
+}
+
+/// Print the given stylesheet AST like [`print_stylesheet`],
+/// and check that no comment is lost in debug build or verify mode.
+/// Warnings found while printing are returned alongside printed code.
+fn print_stylesheet_checked<'s>(
+    stylesheet: &Stylesheet<'s>,
+    comments: &[Comment<'s>],
+    input: &'s str,
+    line_bounds: LineBounds,
+    syntax: Syntax,
+    options: &FormatOptions,
+) -> Result<(String, Vec<Warning>), Error> {
+    let printed_comments = Default::default();
+    let warnings = Default::default();
+    let print_segments = Default::default();
+    let ctx = Ctx {
+        source: Some(input),
+        syntax,
+        options: &options.language,
+        comments,
+        indent_width: options.layout.indent_width,
+        line_bounds: &line_bounds,
+        printed_comments: &printed_comments,
+        initial_options: options,
+        warnings: &warnings,
+        print_segments: &print_segments,
+    };
+    let doc = stylesheet.doc(&ctx, &default_state());
+    check_comments(
+        &ctx,
+        input,
+        &Span {
+            start: 0,
+            end: input.len(),
+        },
+    )?;
+    Ok((
+        print_segmented(doc, print_segments.into_inner(), options),
+        warnings.into_inner(),
+    ))
+}
+
+/// Print the doc and segments split before it, each with its own printer options.
+fn print_segmented<'s>(
+    doc: tiny_pretty::Doc<'s>,
+    segments: PrintSegments<'s>,
+    options: &FormatOptions,
+) -> String {
+    segments
+        .with_last(doc)
+        .fold(String::new(), |mut output, (doc, segment_options)| {
+            output.push_str(&tiny_pretty::print(
+                &doc,
+                &print_options(segment_options.as_deref().unwrap_or(options)),
+            ));
+            output
+        })
+}
+
+/// Report the first comment inside the span that is never printed.
+fn check_comments(ctx: &Ctx, input: &str, span: &Span) -> Result<(), Error> {
+    if !ctx.should_check_comments() {
+        return Ok(());
+    }
+    match ctx.find_lost_comment(span) {
+        Some(comment) => {
+            let (line, col) = ctx
+                .line_bounds
+                .line_col(input, comment.span.start, ColumnUnit::Utf8);
+            Err(Error::LostComment {
+                span: comment.span.clone(),
+                line,
+                col,
+            })
+        }
+        None => Ok(()),
+    }
+}
+
+fn default_state() -> State {
+    State {
         keep_decl_name_case: false,
         selector_override: crate::state::SelectorOverride::Unset,
-    };
-    let doc = stylesheet.doc(&ctx, &state);
-    tiny_pretty::print(
-        &doc,
-        &PrintOptions {
-            indent_kind: if options.layout.use_tabs {
-                IndentKind::Tab
-            } else {
-                IndentKind::Space
-            },
-            line_break: options.layout.line_break.clone().into(),
-            width: options.layout.print_width,
-            tab_size: options.layout.indent_width,
+        options_override: None,
+        indent: 0,
+        top_level: true,
+    }
+}
+
+pub(crate) fn print_options(options: &FormatOptions) -> tiny_pretty::PrintOptions {
+    use tiny_pretty::{IndentKind, PrintOptions};
+
+    PrintOptions {
+        indent_kind: if options.layout.use_tabs {
+            IndentKind::Tab
+        } else {
+            IndentKind::Space
         },
-    )
+        line_break: options.layout.line_break.clone().into(),
+        width: options.layout.print_width,
+        tab_size: options.layout.indent_width,
+    }
 }
 
 /// Detect syntax from file extension.
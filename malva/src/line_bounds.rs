@@ -1,7 +1,24 @@
-use std::{cmp::Ordering, iter, ops::ControlFlow};
+use std::iter;
 
-/// `LineBounds` is used to compute line distance.
-/// This is for internal use only.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// How column numbers are counted.
+pub enum ColumnUnit {
+    /// Count bytes of UTF-8 encoding.
+    #[default]
+    Utf8,
+    /// Count code units of UTF-16 encoding, which is used by LSP and JavaScript.
+    Utf16,
+    /// Count Unicode scalar values, which are `char`s in Rust.
+    Char,
+}
+
+/// `LineBounds` records positions of line breaks,
+/// so it can convert between byte offsets and line/column numbers quickly.
+///
+/// Line numbers and column numbers are 1-based,
+/// and column numbers can be counted in different units (see [`ColumnUnit`]).
+/// Since only line breaks are recorded, the same source code that creates
+/// `LineBounds` must be passed when converting.
 pub struct LineBounds(Vec<usize>);
 
 impl LineBounds {
@@ -14,6 +31,53 @@ impl LineBounds {
         )
     }
 
+    /// Convert byte offset to line number and column number.
+    ///
+    /// Offset that exceeds the end of input will be clamped,
+    /// and offset that isn't at a char boundary will be moved back to the char boundary.
+    pub fn line_col(&self, input: &str, offset: usize, unit: ColumnUnit) -> (usize, usize) {
+        let mut offset = offset.min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_of(offset);
+        let line_start = self.line_start(line);
+        let text = &input[line_start..offset];
+        let col = match unit {
+            ColumnUnit::Utf8 => text.len(),
+            ColumnUnit::Utf16 => text.encode_utf16().count(),
+            ColumnUnit::Char => text.chars().count(),
+        };
+        (line, col + 1)
+    }
+
+    /// Convert line number and column number to byte offset.
+    ///
+    /// Column that exceeds the end of line will be clamped to the end of that line,
+    /// and line that exceeds the end of input will be clamped to the end of input.
+    /// If column is in the middle of a char (such as half of a UTF-16 surrogate pair),
+    /// offset of that char will be returned.
+    pub fn offset(&self, input: &str, line: usize, col: usize, unit: ColumnUnit) -> usize {
+        if line > self.0.len() {
+            return input.len();
+        }
+        let line_start = self.line_start(line.max(1));
+        let line_end = self.0.get(line.max(1)).copied().unwrap_or(input.len());
+        let text = &input[line_start..line_end];
+        let col = col.saturating_sub(1);
+        let mut count = 0;
+        text.char_indices()
+            .find(|(_, c)| {
+                count += match unit {
+                    ColumnUnit::Utf8 => c.len_utf8(),
+                    ColumnUnit::Utf16 => c.len_utf16(),
+                    ColumnUnit::Char => 1,
+                };
+                count > col
+            })
+            .map_or(line_end, |(i, _)| line_start + i)
+    }
+
     pub(crate) fn line_distance(&self, start: usize, end: usize) -> usize {
         debug_assert!(
             end >= start,
@@ -22,20 +86,23 @@ impl LineBounds {
         self.get_line_at(end) - self.get_line_at(start)
     }
 
+    /// Get 1-based line number and 1-based column number in bytes.
     pub(crate) fn get_line_col(&self, pos: usize) -> (usize, usize) {
-        let line = self.get_line_at(pos);
-        (line, pos - self.0[line.saturating_sub(1)])
+        let line = self.line_of(pos);
+        (line, pos - self.line_start(line) + 1)
+    }
+
+    fn line_of(&self, pos: usize) -> usize {
+        // the first element is not a line break
+        self.0[1..].partition_point(|offset| *offset < pos) + 1
+    }
+
+    fn line_start(&self, line: usize) -> usize {
+        if line == 1 { 0 } else { self.0[line - 1] + 1 }
     }
 
     fn get_line_at(&self, pos: usize) -> usize {
-        let (ControlFlow::Break(line) | ControlFlow::Continue(line)) =
-            self.0
-                .iter()
-                .try_fold(0, |i, offset| match pos.cmp(offset) {
-                    Ordering::Less => ControlFlow::Break(i),
-                    Ordering::Equal => ControlFlow::Continue(i),
-                    Ordering::Greater => ControlFlow::Continue(i + 1),
-                });
-        line
+        // line breaks are sorted, so binary search can be used
+        self.0.partition_point(|offset| *offset < pos)
     }
 }
@@ -0,0 +1,105 @@
+use crate::{
+    ctx::Ctx,
+    doc_gen::{apply_options_directives, is_css_modules_export},
+    state::State,
+};
+use raffia::{Spanned, ast::*};
+use std::ops::Range;
+
+/// Find sibling statements that intersect with the given range.
+///
+/// If there's only one statement found and the range is inside one of its blocks,
+/// it will search statements of that block recursively,
+/// so the innermost statements will be returned.
+pub(crate) fn find_statements<'a, 's>(
+    statements: &'a [Statement<'s>],
+    range: &Range<usize>,
+    outer_start: usize,
+    ctx: &Ctx,
+    mut state: State,
+) -> (&'a [Statement<'s>], State) {
+    // for empty range, statement that contains the position will be found
+    let range_end = range.end.max(range.start + 1);
+    let first = statements.partition_point(|stmt| stmt.span().end <= range.start);
+    let last = statements.partition_point(|stmt| stmt.span().start < range_end);
+    let found = &statements[first..last.max(first)];
+
+    // options override directives before found statements also apply to them
+    let pos = statements[..first].iter().fold(outer_start, |pos, stmt| {
+        apply_options_directives(pos, stmt.span().start, ctx, &mut state);
+        stmt.span().end
+    });
+    if let Some(stmt) = found.first() {
+        apply_options_directives(pos, stmt.span().start, ctx, &mut state);
+    }
+
+    if let [stmt] = found {
+        let state = match stmt {
+            Statement::QualifiedRule(qualified_rule) => State {
+                keep_decl_name_case: is_css_modules_export(&qualified_rule.selector),
+                ..state.clone()
+            },
+            _ => state.clone(),
+        };
+        let inner = get_blocks(stmt)
+            .into_iter()
+            .find(|block| block.span.start < range.start && range.end < block.span.end)
+            .map(|block| {
+                let state = State {
+                    top_level: false,
+                    ..state.clone()
+                };
+                find_statements(&block.statements, range, block.span.start, ctx, state)
+            })
+            .filter(|(statements, _)| !statements.is_empty());
+        if let Some(inner) = inner {
+            return inner;
+        }
+    }
+    (found, state)
+}
+
+pub(crate) fn get_blocks<'a, 's>(stmt: &'a Statement<'s>) -> Vec<&'a SimpleBlock<'s>> {
+    match stmt {
+        Statement::AtRule(AtRule {
+            block: Some(block), ..
+        })
+        | Statement::KeyframeBlock(KeyframeBlock { block, .. })
+        | Statement::LessConditionalQualifiedRule(LessConditionalQualifiedRule { block, .. })
+        | Statement::LessMixinDefinition(LessMixinDefinition { block, .. })
+        | Statement::QualifiedRule(QualifiedRule { block, .. }) => vec![block],
+        Statement::UnknownSassAtRule(unknown_sass_at_rule) => {
+            unknown_sass_at_rule.block.iter().collect()
+        }
+        Statement::SassIfAtRule(sass_if_at_rule) => {
+            let mut blocks = vec![&sass_if_at_rule.if_clause.block];
+            blocks.extend(
+                sass_if_at_rule
+                    .else_if_clauses
+                    .iter()
+                    .map(|clause| &clause.block),
+            );
+            blocks.extend(&sass_if_at_rule.else_clause);
+            blocks
+        }
+        Statement::Declaration(Declaration { value, .. }) => match value.last() {
+            Some(ComponentValue::SassNestingDeclaration(SassNestingDeclaration {
+                block, ..
+            })) => vec![block],
+            _ => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+/// Semicolon after statement isn't part of statement span,
+/// but formatter will print it, so we need to replace it, too.
+pub(crate) fn end_with_semicolon(source: &str, end: usize) -> usize {
+    let rest = &source[end..];
+    let trimmed = rest.trim_start();
+    if trimmed.starts_with(';') {
+        end + (rest.len() - trimmed.len()) + 1
+    } else {
+        end
+    }
+}
@@ -1,7 +1,17 @@
+use crate::config::FormatOptions;
+use std::rc::Rc;
+
 #[derive(Clone)]
 pub(crate) struct State {
     pub(crate) keep_decl_name_case: bool,
     pub(crate) selector_override: SelectorOverride,
+    /// Options overridden by comment directives in current block or outer blocks.
+    pub(crate) options_override: Option<Rc<FormatOptions>>,
+    /// Indentation width of current block.
+    pub(crate) indent: usize,
+    /// Whether current statements are at the top level of formatted code,
+    /// which can be printed with different printer options.
+    pub(crate) top_level: bool,
 }
 
 #[derive(Clone)]
@@ -0,0 +1,230 @@
+use crate::{
+    ColumnUnit, Error, LineBounds, apply_config_comment, config::FormatOptions,
+    embedded::relocate_error, is_file_ignored, print_stylesheet_checked, verify_output,
+};
+use raffia::{Span, Syntax, ast::Stylesheet, token::Comment};
+use std::ops::Range;
+
+pub(crate) fn format(input: &str, syntax: Syntax, options: &FormatOptions) -> (String, Vec<Error>) {
+    let comments = parse_leading_comment(input, syntax);
+    let overridden = match apply_config_comment(&comments, options, &mut vec![]) {
+        Ok(overridden) => overridden,
+        Err(error) => return (input.to_owned(), vec![error]),
+    };
+    let options = overridden.as_deref().unwrap_or(options);
+    if is_file_ignored(&comments, options) {
+        return (input.to_owned(), vec![]);
+    }
+
+    let mut formatter = TolerantFormatter {
+        input,
+        syntax,
+        options,
+        line_bounds: LineBounds::new(input),
+        output: String::with_capacity(input.len()),
+        errors: vec![],
+    };
+    let chunks = split_top_level(input, syntax);
+    formatter.format_chunks(&chunks);
+    (formatter.output, formatter.errors)
+}
+
+struct TolerantFormatter<'a> {
+    input: &'a str,
+    syntax: Syntax,
+    options: &'a FormatOptions,
+    line_bounds: LineBounds,
+    output: String,
+    errors: Vec<Error>,
+}
+
+impl TolerantFormatter<'_> {
+    fn format_chunks(&mut self, chunks: &[Range<usize>]) {
+        let (Some(first), Some(last)) = (chunks.first(), chunks.last()) else {
+            return;
+        };
+        let start = first.start;
+        let source = &self.input[start..last.end];
+        if source.trim().is_empty() {
+            return;
+        }
+
+        let mut comments = vec![];
+        match crate::parse(source, self.syntax, &mut comments) {
+            Ok(stylesheet) => {
+                self.push_separator(source);
+                match self.print(&stylesheet, &comments, source) {
+                    Ok(output) => self.output.push_str(&output),
+                    Err(error) => {
+                        self.output.push_str(source.trim());
+                        self.output.push('\n');
+                        self.errors.push(relocate_error(
+                            error,
+                            |offset| offset + start,
+                            self.input,
+                            &self.line_bounds,
+                        ));
+                    }
+                }
+            }
+            Err(mut error) => {
+                error.span = Span {
+                    start: error.span.start + start,
+                    end: error.span.end + start,
+                };
+                let index = chunks
+                    .partition_point(|chunk| chunk.end <= error.span.start)
+                    .min(chunks.len() - 1);
+
+                // Syntax error may be caused by previous statements, such as unclosed brackets,
+                // so we check if this statement itself is valid.
+                if index > 0
+                    && crate::parse(&self.input[chunks[index].clone()], self.syntax, &mut vec![])
+                        .is_ok()
+                {
+                    self.format_chunks(&chunks[..index]);
+                    self.format_chunks(&chunks[index..]);
+                    return;
+                }
+
+                self.format_chunks(&chunks[..index]);
+
+                let source = &self.input[chunks[index].clone()];
+                self.push_separator(source);
+                self.output.push_str(source.trim());
+                self.output.push('\n');
+                let (line, col) =
+                    self.line_bounds
+                        .line_col(self.input, error.span.start, ColumnUnit::Utf8);
+                self.errors.push(Error::Parser(error, line, col));
+
+                self.format_chunks(&chunks[index + 1..]);
+            }
+        }
+    }
+
+    /// Print parsed chunks, and verify them if it's enabled.
+    fn print<'s>(
+        &self,
+        stylesheet: &Stylesheet<'s>,
+        comments: &[Comment<'s>],
+        source: &'s str,
+    ) -> Result<String, Error> {
+        let (output, _) = print_stylesheet_checked(
+            stylesheet,
+            comments,
+            source,
+            LineBounds::new(source),
+            self.syntax,
+            self.options,
+        )?;
+        verify_output(source, stylesheet, &output, self.syntax, self.options)?;
+        Ok(output)
+    }
+
+    /// Keep an empty line between chunks if there was one in source code.
+    fn push_separator(&mut self, source: &str) {
+        let leading_whitespaces = &source[..source.len() - source.trim_start().len()];
+        if !self.output.is_empty() && leading_whitespaces.matches('\n').count() > 1 {
+            self.output.push('\n');
+        }
+    }
+}
+
+/// Directives for the whole file are only recognized in the comment at the beginning,
+/// so only that comment is parsed, since the rest of code may contain syntax errors.
+fn parse_leading_comment(input: &str, syntax: Syntax) -> Vec<Comment<'_>> {
+    let end = if input.starts_with("/*") {
+        input.find("*/").map(|end| end + 2)
+    } else if input.starts_with("//") && syntax != Syntax::Css {
+        Some(input.find('\n').unwrap_or(input.len()))
+    } else {
+        None
+    };
+    let mut comments = vec![];
+    if let Some(end) = end {
+        let _ = crate::parse(&input[..end], syntax, &mut comments);
+    }
+    comments
+}
+
+/// Split source code into top-level chunks without parsing.
+/// Leading whitespaces and comments are included in the chunk of the following statement.
+fn split_top_level(input: &str, syntax: Syntax) -> Vec<Range<usize>> {
+    if syntax == Syntax::Sass {
+        return split_sass_top_level(input);
+    }
+
+    let bytes = input.as_bytes();
+    let mut chunks = vec![];
+    let mut chunk_start = 0;
+    let mut braces = 0usize;
+    let mut parens = 0usize;
+    let mut i = 0;
+    while let Some(&byte) = bytes.get(i) {
+        match byte {
+            b'"' | b'\'' => {
+                i += 1;
+                while let Some(&b) = bytes.get(i) {
+                    if b == b'\\' {
+                        i += 1;
+                    } else if b == byte || b == b'\n' {
+                        break;
+                    }
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = memchr::memmem::find(&bytes[i + 2..], b"*/")
+                    .map_or(bytes.len(), |end| i + 2 + end + 1);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') && syntax != Syntax::Css && parens == 0 => {
+                i = memchr::memchr(b'\n', &bytes[i..]).map_or(bytes.len(), |end| i + end);
+            }
+            b'\\' => i += 1,
+            b'(' | b'[' => parens += 1,
+            b')' | b']' => parens = parens.saturating_sub(1),
+            b'{' => braces += 1,
+            b'}' => {
+                braces = braces.saturating_sub(1);
+                if braces == 0 {
+                    parens = 0;
+                    chunks.push(chunk_start..i + 1);
+                    chunk_start = i + 1;
+                }
+            }
+            b';' if braces == 0 && parens == 0 => {
+                chunks.push(chunk_start..i + 1);
+                chunk_start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    let end = input.len();
+    if chunk_start < end {
+        chunks.push(chunk_start..end);
+    }
+    chunks
+}
+
+/// In Sass, top-level statements start at lines without indentation.
+fn split_sass_top_level(input: &str) -> Vec<Range<usize>> {
+    let mut chunks = vec![];
+    let mut chunk_start = 0;
+    let mut line_start = 0;
+    for line in input.split_inclusive('\n') {
+        if line_start > 0
+            && line.starts_with(|c: char| !c.is_whitespace())
+            && !input[chunk_start..line_start].trim().is_empty()
+        {
+            chunks.push(chunk_start..line_start);
+            chunk_start = line_start;
+        }
+        line_start += line.len();
+    }
+    if chunk_start < input.len() {
+        chunks.push(chunk_start..input.len());
+    }
+    chunks
+}
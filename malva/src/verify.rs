@@ -0,0 +1,851 @@
+use crate::{
+    ColumnUnit, Error, LineBounds, apply_config_comment, build_parser, config::FormatOptions,
+    print_resolved, resolve_options,
+};
+use raffia::{Span, Syntax, ast::Stylesheet, token::Comment};
+use serde::{Serialize, ser};
+use std::fmt::Display;
+
+/// Check that formatted code can be parsed into the same syntax tree as the original code,
+/// and formatting it again produces the same result.
+pub(crate) fn verify(
+    input: &str,
+    stylesheet: &Stylesheet,
+    output: &str,
+    syntax: Syntax,
+    options: &FormatOptions,
+) -> Result<(), Error> {
+    // formatted code is parsed before anything else to keep this stack frame small,
+    // since parsing deeply nested code requires deep recursion
+    let mut comments = vec![];
+    let formatted = build_parser(output, syntax, &mut comments).parse::<Stylesheet>();
+    compare_output(
+        input, stylesheet, output, formatted, &comments, syntax, options,
+    )
+}
+
+fn compare_output<'s>(
+    input: &str,
+    stylesheet: &Stylesheet,
+    output: &'s str,
+    formatted: Result<Stylesheet<'s>, raffia::error::Error>,
+    comments: &[Comment<'s>],
+    syntax: Syntax,
+    options: &FormatOptions,
+) -> Result<(), Error> {
+    let formatted = formatted
+        .map_err(|error| unstable(input, map_to_input(input, output, error.span.start)))?;
+    // options are resolved in the same way as formatting the output again
+    let resolved = resolve_options(options, syntax)?;
+    let options = resolved.as_deref().unwrap_or(options);
+    let overridden = apply_config_comment(comments, options, &mut vec![])?;
+    let options = overridden.as_deref().unwrap_or(options);
+
+    let comparator = Comparator {
+        unordered_declarations: options.language.declaration_order.is_some(),
+    };
+    let old = Flattener::flatten(stylesheet)?;
+    let new = Flattener::flatten(&formatted)?;
+    comparator
+        .compare(&old, &new, &Span { start: 0, end: 0 })
+        .map_err(|span| unstable(input, span.start))?;
+
+    let regression = print_resolved(
+        &formatted,
+        comments,
+        Some(output),
+        LineBounds::new(output),
+        syntax,
+        options,
+    );
+    if let Some(offset) = output
+        .char_indices()
+        .zip(regression.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, _), _)| i)
+        .or_else(|| (output.len() != regression.len()).then(|| output.len().min(regression.len())))
+    {
+        return Err(unstable(input, map_to_input(input, output, offset)));
+    }
+    Ok(())
+}
+
+fn unstable(input: &str, start: usize) -> Error {
+    let (line, col) = LineBounds::new(input).line_col(input, start, ColumnUnit::Utf8);
+    Error::UnstableOutput {
+        span: Span { start, end: start },
+        line,
+        col,
+    }
+}
+
+/// Nodes whose identifiers are case-sensitive.
+/// Identifiers in other places, such as keywords and units, may be lowercased by formatter.
+const CASE_SENSITIVE_NODES: [&str; 6] = [
+    "ClassSelector",
+    "IdSelector",
+    "KeyframesName",
+    "LessVariable",
+    "SassPlaceholderSelector",
+    "SassVariable",
+];
+
+/// Syntax tree is flattened into a sequence of tokens,
+/// so it can be compared without building nested values.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// Start of a node with its type name and span.
+    Node(&'static str, Option<Span>),
+    /// Start of a list.
+    Seq,
+    /// End of a node or a list.
+    End,
+    Field(&'static str),
+    Str(String),
+    Number(f64),
+    Bool(bool),
+    Variant(&'static str),
+    None,
+}
+
+impl Token {
+    /// Compare tokens while ignoring spans.
+    fn same(&self, other: &Token) -> bool {
+        match (self, other) {
+            (Token::Node(a, _), Token::Node(b, _)) => a == b,
+            _ => self == other,
+        }
+    }
+}
+
+/// Find the index of `End` token that closes the node or list starting at `start`.
+fn find_end(tokens: &[Token], start: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Node(..) | Token::Seq => depth += 1,
+            Token::End => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// Split tokens of list items into ranges of each item.
+fn split_items(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut items = vec![];
+    let mut start = 0;
+    while start < tokens.len() {
+        let end = match tokens[start] {
+            Token::Node(..) | Token::Seq => find_end(tokens, start) + 1,
+            _ => start + 1,
+        };
+        items.push(&tokens[start..end.min(tokens.len())]);
+        start = end;
+    }
+    items
+}
+
+struct Frame {
+    /// Index of the starting token.
+    start: usize,
+    /// Node type name, or `None` for lists.
+    name: Option<&'static str>,
+    case_sensitive: bool,
+}
+
+/// Serializer that flattens syntax tree into tokens,
+/// and converts nodes that are rewritten by formatter into equivalent forms.
+#[derive(Default)]
+struct Flattener {
+    tokens: Vec<Token>,
+    frames: Vec<Frame>,
+}
+
+impl Flattener {
+    fn flatten(stylesheet: &Stylesheet) -> Result<Vec<Token>, Error> {
+        let mut flattener = Flattener::default();
+        stylesheet
+            .serialize(&mut flattener)
+            .map_err(|FlattenError(message)| Error::Verify(message))?;
+        Ok(flattener.tokens)
+    }
+
+    fn start(&mut self, name: Option<&'static str>) {
+        let start = self.tokens.len();
+        self.tokens.push(match name {
+            Some(name) => Token::Node(name, None),
+            None => Token::Seq,
+        });
+        let case_sensitive = self.frames.last().is_some_and(|frame| frame.case_sensitive)
+            || name.is_some_and(|name| CASE_SENSITIVE_NODES.contains(&name));
+        self.frames.push(Frame {
+            start,
+            name,
+            case_sensitive,
+        });
+    }
+
+    fn end(&mut self) {
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+        self.tokens.push(Token::End);
+        let Some(name) = frame.name else {
+            return;
+        };
+        let parent = self.frames.last().and_then(|frame| frame.name);
+        let field = frame
+            .start
+            .checked_sub(1)
+            .and_then(|i| match self.tokens[i] {
+                Token::Field(field) => Some(field),
+                _ => None,
+            });
+        match name {
+            // spans are ignored, except the node span which is used for reporting
+            "Span" => {
+                let span = match &self.tokens[frame.start..] {
+                    [
+                        _,
+                        Token::Field("start"),
+                        Token::Number(start),
+                        Token::Field("end"),
+                        Token::Number(end),
+                        Token::End,
+                    ] => Some(Span {
+                        start: *start as usize,
+                        end: *end as usize,
+                    }),
+                    _ => None,
+                };
+                self.tokens.truncate(frame.start);
+                if field == Some("span") {
+                    self.tokens.pop();
+                    if let Some(Token::Node(_, node_span)) = self
+                        .frames
+                        .last()
+                        .and_then(|frame| self.tokens.get_mut(frame.start))
+                    {
+                        *node_span = span;
+                    }
+                }
+            }
+            // attribute value may be quoted
+            _ if parent == Some("AttributeSelector") && field == Some("value") => {
+                self.normalize_attr_value(frame.start);
+            }
+            "Ident" if !frame.case_sensitive => {
+                if let Some(Token::Str(name)) = self.find_field(frame.start, "name")
+                    && !name.starts_with("--")
+                {
+                    name.make_ascii_lowercase();
+                }
+                // `from` and `to` may be printed as `0%` and `100%`
+                if self.is_in_list(parent, "KeyframeBlock", "selectors") {
+                    let percentage = match self.find_field(frame.start, "name") {
+                        Some(Token::Str(name)) if name == "from" => 0.0,
+                        Some(Token::Str(name)) if name == "to" => 100.0,
+                        _ => return,
+                    };
+                    self.tokens.truncate(frame.start);
+                    self.tokens.extend([
+                        Token::Node("Percentage", None),
+                        Token::Field("type"),
+                        Token::Str("Percentage".into()),
+                        Token::Field("value"),
+                        Token::Node("Number", None),
+                        Token::Field("type"),
+                        Token::Str("Number".into()),
+                        Token::Field("value"),
+                        Token::Number(percentage),
+                        Token::End,
+                        Token::End,
+                    ]);
+                }
+            }
+            "HexColor" => {
+                if let Some(Token::Str(value)) = self.find_field(frame.start, "value") {
+                    value.make_ascii_lowercase();
+                    if value.len() == 3 || value.len() == 4 {
+                        *value = value.chars().flat_map(|c| [c, c]).collect();
+                    }
+                }
+            }
+            // `+ 1px` may be printed as `+1px` in Sass
+            "SassUnaryExpression" => self.fold_unary_expression(frame.start),
+            // separator doesn't matter if there's only one item
+            "LessMixinArguments" | "LessMixinParameters" => {
+                let items = if name == "LessMixinArguments" {
+                    "args"
+                } else {
+                    "params"
+                };
+                let is_single = self
+                    .field_index(frame.start, items)
+                    .filter(|i| self.tokens.get(i + 1) == Some(&Token::Seq))
+                    .is_some_and(|i| {
+                        let end = find_end(&self.tokens, i + 1);
+                        split_items(&self.tokens[i + 2..end]).len() <= 1
+                    });
+                if is_single
+                    && let Some(Token::Bool(value)) =
+                        self.find_field(frame.start, "isCommaSeparated")
+                {
+                    *value = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Find the index of field token that is a direct child of the node starting at `start`.
+    fn field_index(&self, start: usize, name: &str) -> Option<usize> {
+        let mut i = start + 1;
+        while i < self.tokens.len() {
+            match &self.tokens[i] {
+                Token::Field(field) if *field == name => return Some(i),
+                Token::Node(..) | Token::Seq => i = find_end(&self.tokens, i) + 1,
+                _ => i += 1,
+            }
+        }
+        None
+    }
+
+    /// Find the value token of field that is a direct child of the node starting at `start`.
+    fn find_field(&mut self, start: usize, name: &str) -> Option<&mut Token> {
+        let i = self.field_index(start, name)?;
+        self.tokens.get_mut(i + 1)
+    }
+
+    /// Check if current node is an item of the list in specific field of parent node.
+    fn is_in_list(&self, parent: Option<&str>, node: &str, field: &str) -> bool {
+        let [.., grandparent, list] = &self.frames[..] else {
+            return false;
+        };
+        parent.is_none()
+            && list.name.is_none()
+            && grandparent.name == Some(node)
+            && list
+                .start
+                .checked_sub(1)
+                .is_some_and(|i| matches!(self.tokens[i], Token::Field(name) if name == field))
+    }
+
+    /// Fold sign of unary expression into the number.
+    fn fold_unary_expression(&mut self, start: usize) {
+        let sign = match self.field_index(start, "op").and_then(|i| {
+            self.tokens[i + 1..].iter().find_map(|token| match token {
+                Token::Variant(kind) => Some(*kind),
+                _ => None,
+            })
+        }) {
+            Some("Plus") => 1.0,
+            Some("Minus") => -1.0,
+            _ => return,
+        };
+        let Some(expr) = self.field_index(start, "expr").map(|i| i + 1) else {
+            return;
+        };
+        if !matches!(
+            self.tokens.get(expr),
+            Some(Token::Node("Number" | "Dimension" | "Percentage", _))
+        ) {
+            return;
+        }
+        let end = find_end(&self.tokens, expr);
+        if let Some(Token::Number(value)) = self.tokens[expr..end]
+            .iter_mut()
+            .find(|token| matches!(token, Token::Number(..)))
+        {
+            *value *= sign;
+        }
+        self.tokens.truncate(end + 1);
+        self.tokens.drain(start..expr);
+    }
+
+    /// Convert both quoted and unquoted attribute values into the same form.
+    fn normalize_attr_value(&mut self, start: usize) {
+        match self.tokens[start] {
+            Token::Node("Ident", _) => {
+                let Some(Token::Str(name)) = self.find_field(start, "name").cloned() else {
+                    return;
+                };
+                self.tokens.truncate(start);
+                self.tokens.extend([
+                    Token::Node("Str", None),
+                    Token::Field("type"),
+                    Token::Str("Str".into()),
+                    Token::Field("value"),
+                    Token::Str(name),
+                    Token::End,
+                ]);
+            }
+            Token::Node(..) => {
+                let Some(elements) = self
+                    .field_index(start, "elements")
+                    .filter(|i| self.tokens.get(i + 1) == Some(&Token::Seq))
+                else {
+                    return;
+                };
+                let end = find_end(&self.tokens, elements + 1);
+                let mut tokens = vec![
+                    Token::Node("Interpolated", None),
+                    Token::Field("type"),
+                    Token::Str("Interpolated".into()),
+                    Token::Field("elements"),
+                    Token::Seq,
+                ];
+                for element in split_items(&self.tokens[elements + 2..end]) {
+                    match element {
+                        [Token::Node(name, _), ..] if name.ends_with("StaticPart") => {
+                            let value = element.windows(2).find_map(|pair| match pair {
+                                [Token::Field("value"), Token::Str(value)] => Some(value),
+                                _ => None,
+                            });
+                            if let Some(value) = value.filter(|value| !value.is_empty()) {
+                                tokens.extend([
+                                    Token::Node("StaticPart", None),
+                                    Token::Field("type"),
+                                    Token::Str("StaticPart".into()),
+                                    Token::Field("value"),
+                                    Token::Str(value.clone()),
+                                    Token::End,
+                                ]);
+                            }
+                        }
+                        _ => tokens.extend_from_slice(element),
+                    }
+                }
+                tokens.extend([Token::End, Token::End]);
+                self.tokens.truncate(start);
+                self.tokens.extend(tokens);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FlattenError(String);
+
+impl Display for FlattenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for FlattenError {}
+
+impl ser::Error for FlattenError {
+    fn custom<T: Display>(msg: T) -> Self {
+        FlattenError(msg.to_string())
+    }
+}
+
+impl ser::Serializer for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), FlattenError> {
+        self.tokens.push(Token::Bool(v));
+        Ok(())
+    }
+    fn serialize_i8(self, v: i8) -> Result<(), FlattenError> {
+        self.serialize_f64(v.into())
+    }
+    fn serialize_i16(self, v: i16) -> Result<(), FlattenError> {
+        self.serialize_f64(v.into())
+    }
+    fn serialize_i32(self, v: i32) -> Result<(), FlattenError> {
+        self.serialize_f64(v.into())
+    }
+    fn serialize_i64(self, v: i64) -> Result<(), FlattenError> {
+        self.serialize_f64(v as f64)
+    }
+    fn serialize_u8(self, v: u8) -> Result<(), FlattenError> {
+        self.serialize_f64(v.into())
+    }
+    fn serialize_u16(self, v: u16) -> Result<(), FlattenError> {
+        self.serialize_f64(v.into())
+    }
+    fn serialize_u32(self, v: u32) -> Result<(), FlattenError> {
+        self.serialize_f64(v.into())
+    }
+    fn serialize_u64(self, v: u64) -> Result<(), FlattenError> {
+        self.serialize_f64(v as f64)
+    }
+    fn serialize_f32(self, v: f32) -> Result<(), FlattenError> {
+        self.serialize_f64(v.into())
+    }
+    fn serialize_f64(self, v: f64) -> Result<(), FlattenError> {
+        self.tokens.push(Token::Number(v));
+        Ok(())
+    }
+    fn serialize_char(self, v: char) -> Result<(), FlattenError> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+    fn serialize_str(self, v: &str) -> Result<(), FlattenError> {
+        self.tokens.push(Token::Str(v.to_owned()));
+        Ok(())
+    }
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), FlattenError> {
+        Err(ser::Error::custom("bytes are not supported"))
+    }
+    fn serialize_none(self) -> Result<(), FlattenError> {
+        self.tokens.push(Token::None);
+        Ok(())
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), FlattenError> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<(), FlattenError> {
+        self.serialize_none()
+    }
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), FlattenError> {
+        self.serialize_none()
+    }
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<(), FlattenError> {
+        self.tokens.push(Token::Variant(variant));
+        Ok(())
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        self.tokens.push(Token::Variant(variant));
+        value.serialize(self)
+    }
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self, FlattenError> {
+        self.start(None);
+        Ok(self)
+    }
+    fn serialize_tuple(self, _: usize) -> Result<Self, FlattenError> {
+        self.serialize_seq(None)
+    }
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self, FlattenError> {
+        self.serialize_seq(None)
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self, FlattenError> {
+        self.tokens.push(Token::Variant(variant));
+        self.serialize_seq(None)
+    }
+    fn serialize_map(self, _: Option<usize>) -> Result<Self, FlattenError> {
+        self.serialize_seq(None)
+    }
+    fn serialize_struct(self, name: &'static str, _: usize) -> Result<Self, FlattenError> {
+        self.start(Some(name));
+        Ok(self)
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self, FlattenError> {
+        self.start(Some(variant));
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), FlattenError> {
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        Flattener::end(self);
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), FlattenError> {
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        Flattener::end(self);
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), FlattenError> {
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        Flattener::end(self);
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), FlattenError> {
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        Flattener::end(self);
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), FlattenError> {
+        key.serialize(&mut **self)
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), FlattenError> {
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        Flattener::end(self);
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        // raw text is different from the original code after formatting
+        if key == "raw" {
+            return Ok(());
+        }
+        self.tokens.push(Token::Field(key));
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        Flattener::end(self);
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        Flattener::end(self);
+        Ok(())
+    }
+}
+
+struct Comparator {
+    /// Declarations may be sorted by formatter.
+    unordered_declarations: bool,
+}
+
+impl Comparator {
+    /// Compare flattened syntax trees,
+    /// then return span of the innermost different node in the original code.
+    fn compare(&self, old: &[Token], new: &[Token], span: &Span) -> Result<(), Span> {
+        let mut spans = vec![span.clone()];
+        let (mut i, mut j) = (0, 0);
+        while let Some(token) = old.get(i) {
+            match token {
+                Token::Node(_, Some(span)) => spans.push(span.clone()),
+                Token::Node(..) | Token::Seq => spans.push(spans[spans.len() - 1].clone()),
+                _ => {}
+            }
+            let span = &spans[spans.len() - 1];
+            if !new.get(j).is_some_and(|other| token.same(other)) {
+                return Err(span.clone());
+            }
+            if self.unordered_declarations
+                && *token == Token::Field("statements")
+                && old.get(i + 1) == Some(&Token::Seq)
+                && new.get(j + 1) == Some(&Token::Seq)
+            {
+                let old_end = find_end(old, i + 1);
+                let new_end = find_end(new, j + 1);
+                self.compare_statements(
+                    &old[(i + 2).min(old_end)..old_end],
+                    &new[(j + 2).min(new_end)..new_end],
+                    span,
+                )?;
+                i = old_end + 1;
+                j = new_end + 1;
+                continue;
+            }
+            if *token == Token::End {
+                spans.pop();
+            }
+            i += 1;
+            j += 1;
+        }
+        if j == new.len() {
+            Ok(())
+        } else {
+            Err(spans.swap_remove(0))
+        }
+    }
+
+    /// Compare statements in order, except that declarations in each run of adjacent declarations
+    /// can be reordered, since formatter only sorts declarations inside those runs.
+    fn compare_statements(&self, old: &[Token], new: &[Token], span: &Span) -> Result<(), Span> {
+        let old_items = split_items(old);
+        let new_items = split_items(new);
+        if old_items.len() != new_items.len() {
+            return Err(span.clone());
+        }
+        let mut i = 0;
+        while i < old_items.len() {
+            let run = old_items[i..]
+                .iter()
+                .take_while(|item| is_sortable_declaration(item))
+                .count()
+                .max(1);
+            if run == 1 {
+                self.compare(old_items[i], new_items[i], span)?;
+            } else {
+                self.compare_unordered(&old_items[i..i + run], &new_items[i..i + run], span)?;
+            }
+            i += run;
+        }
+        Ok(())
+    }
+
+    fn compare_unordered(
+        &self,
+        old_items: &[&[Token]],
+        new_items: &[&[Token]],
+        span: &Span,
+    ) -> Result<(), Span> {
+        let mut used = vec![false; new_items.len()];
+        old_items.iter().try_for_each(|old| {
+            let index = new_items
+                .iter()
+                .enumerate()
+                .position(|(i, new)| !used[i] && self.compare(old, new, span).is_ok())
+                .ok_or_else(|| match old.first() {
+                    Some(Token::Node(_, Some(node_span))) => node_span.clone(),
+                    _ => span.clone(),
+                })?;
+            used[index] = true;
+            Ok(())
+        })
+    }
+}
+
+/// Check if the statement is a declaration with literal name,
+/// which is the only kind of statement that can be sorted by formatter.
+fn is_sortable_declaration(tokens: &[Token]) -> bool {
+    if !matches!(tokens.first(), Some(Token::Node("Declaration", _))) {
+        return false;
+    }
+    let mut i = 1;
+    while let Some(token) = tokens.get(i) {
+        match token {
+            Token::Field("name") => {
+                return matches!(tokens.get(i + 1), Some(Token::Node("Ident", _)));
+            }
+            Token::Node(..) | Token::Seq => i = find_end(tokens, i) + 1,
+            _ => i += 1,
+        }
+    }
+    false
+}
+
+/// Map offset of formatted code to the original code by counting non-whitespace characters,
+/// since formatter mostly changes whitespaces only.
+fn map_to_input(input: &str, output: &str, offset: usize) -> usize {
+    let count = output[..offset]
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .count();
+    input
+        .char_indices()
+        .filter(|(_, c)| !c.is_whitespace())
+        .nth(count)
+        .map_or(input.len(), |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DeclarationOrder;
+
+    fn verify_output(input: &str, output: &str) -> Result<(), Error> {
+        let mut options = FormatOptions::default();
+        options.language.declaration_order = Some(DeclarationOrder::Alphabetical);
+        let mut comments = vec![];
+        let stylesheet = crate::parse(input, Syntax::Css, &mut comments).unwrap();
+        verify(input, &stylesheet, output, Syntax::Css, &options)
+    }
+
+    #[test]
+    fn reordered_declarations() {
+        let input = "a {\n  top: 0;\n  color: red;\n}\n";
+        let output = "a {\n  color: red;\n  top: 0;\n}\n";
+        assert!(verify_output(input, output).is_ok());
+    }
+
+    #[test]
+    fn reordered_rules() {
+        let input = "a {\n  color: red;\n}\nb {\n  top: 0;\n}\n";
+        let output = "b {\n  top: 0;\n}\na {\n  color: red;\n}\n";
+        assert!(matches!(
+            verify_output(input, output),
+            Err(Error::UnstableOutput { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn declarations_moved_across_rule() {
+        let input = "a {\n  color: red;\n  b {}\n  top: 0;\n}\n";
+        let output = "a {\n  top: 0;\n  b {}\n  color: red;\n}\n";
+        assert!(verify_output(input, output).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use malva::{
+    Error,
+    config::Quotes,
+    config_file::{ConfigFile, find_config_file, resolve_options},
+};
+use std::fs;
+
+#[test]
+fn discover_and_override() {
+    let dir = std::env::temp_dir().join(format!("malva_config_file_test_{}", std::process::id()));
+    fs::create_dir_all(dir.join("legacy/nested")).unwrap();
+    fs::create_dir_all(dir.join("packages/new")).unwrap();
+    fs::create_dir_all(dir.join("packages/root")).unwrap();
+    fs::write(
+        dir.join("malva.toml"),
+        r#"
+printWidth = 100
+quotes = "preferSingle"
+
+[[overrides]]
+files = "*.less"
+options = { printWidth = 120 }
+
+[[overrides]]
+files = ["legacy/**/*.less"]
+options = { indentWidth = 4 }
+
+[[overrides]]
+files = "legacy/**/*.scss"
+options = { scss = { quotes = "alwaysSingle" } }
+
+[scss]
+printWidth = 90
+"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("packages/new/.malvarc.json"),
+        r#"{ "useTabs": true, "print_width": 110 }"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("packages/root/malva.toml"),
+        "root = true\nuseTabs = true\n",
+    )
+    .unwrap();
+
+    let options = resolve_options(dir.join("a.scss")).unwrap();
+    assert_eq!(options.layout.print_width, 100);
+    assert_eq!(options.layout.indent_width, 2);
+
+    let options = resolve_options(dir.join("legacy/nested/a.less")).unwrap();
+    assert_eq!(options.layout.print_width, 120);
+    assert_eq!(options.layout.indent_width, 4);
+
+    let options = resolve_options(dir.join("b.less")).unwrap();
+    assert_eq!(options.layout.print_width, 120);
+    assert_eq!(options.layout.indent_width, 2);
+
+    assert_eq!(
+        find_config_file(dir.join("packages/new/a.css")),
+        Some(dir.join("packages/new/.malvarc.json"))
+    );
+    // options in parent directories are merged
+    let options = resolve_options(dir.join("packages/new/a.css")).unwrap();
+    assert!(options.layout.use_tabs);
+    assert_eq!(options.layout.print_width, 110);
+    assert!(matches!(options.language.quotes, Quotes::PreferSingle));
+
+    let options = resolve_options(dir.join("packages/root/a.css")).unwrap();
+    assert!(options.layout.use_tabs);
+    assert_eq!(options.layout.print_width, 80);
+    assert!(matches!(options.language.quotes, Quotes::AlwaysDouble));
+
+    // nested options are merged deeply
+    let options = resolve_options(dir.join("legacy/a.scss"))
+        .unwrap()
+        .resolve(malva::Syntax::Scss)
+        .into_owned();
+    assert_eq!(options.layout.print_width, 90);
+    assert!(matches!(options.language.quotes, Quotes::AlwaysSingle));
+
+    let config = ConfigFile::load(dir.join("malva.toml")).unwrap();
+    assert_eq!(
+        config
+            .options_for(dir.join("packages/new/a.less"))
+            .unwrap()
+            .layout
+            .print_width,
+        120
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn invalid_config_file() {
+    let dir =
+        std::env::temp_dir().join(format!("malva_invalid_config_test_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    assert!(matches!(
+        ConfigFile::load(dir.join("malva.toml")),
+        Err(Error::Io(..))
+    ));
+
+    fs::write(
+        dir.join("malva.toml"),
+        "[[overrides]]\nfiles = [\"*.css\", 1]\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        ConfigFile::load(dir.join("malva.toml")),
+        Err(Error::InvalidOptions(..))
+    ));
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,176 @@
+use malva::{Error, Syntax, config::FormatOptions, convert_syntax};
+
+#[test]
+fn sass_to_scss() {
+    let input = "\
+// colors
+$primary: #333
+
+a,
+b
+  color: $primary
+  font:
+    family: serif
+  @if $primary == red
+    margin: 0
+  @else
+    margin: 1px
+";
+    let output =
+        convert_syntax(input, Syntax::Sass, Syntax::Scss, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        output,
+        "\
+// colors
+$primary: #333;
+
+a,
+b {
+  color: $primary;
+  font: {
+    family: serif;
+  }
+  @if $primary == red {
+    margin: 0;
+  } @else {
+    margin: 1px;
+  }
+}
+"
+    );
+}
+
+#[test]
+fn scss_to_sass() {
+    let input = "\
+/* base styles */
+@use 'sass:math';
+
+a, b {
+  color: red;
+  // line comment
+  font: bold {
+    family: serif;
+  }
+  &:hover { color: blue; }
+}
+";
+    let output =
+        convert_syntax(input, Syntax::Scss, Syntax::Sass, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        output,
+        "\
+/* base styles */
+@use \"sass:math\"
+
+a, b
+  color: red
+  // line comment
+  font: bold
+    family: serif
+  &:hover
+    color: blue
+"
+    );
+}
+
+#[test]
+fn round_trip() {
+    let options = FormatOptions::default();
+    let input = "\
+.nav,
+.menu
+  display: flex
+  /* multi-line
+     comment */
+  gap: 0
+  @if $a
+    color: red
+  @else if $b
+    color: blue
+";
+    let scss = convert_syntax(input, Syntax::Sass, Syntax::Scss, &options).unwrap();
+    let sass = convert_syntax(&scss, Syntax::Scss, Syntax::Sass, &options).unwrap();
+    assert_eq!(sass, input);
+}
+
+#[test]
+fn multi_line_selectors() {
+    let input = "\
+[title=\"a: b\"],
+.c
+  .d,
+  .e,
+  .f
+    margin: 0
+";
+    let output =
+        convert_syntax(input, Syntax::Sass, Syntax::Scss, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        output,
+        "\
+[title=\"a: b\"],
+.c {
+  .d,
+  .e,
+  .f {
+    margin: 0;
+  }
+}
+"
+    );
+}
+
+#[test]
+fn long_values_to_sass() {
+    let input = "\
+a {
+  transition: opacity 0.3s ease-in-out, transform 0.3s ease-in-out, visibility 0.3s linear;
+  @include foo(aaaaaaaaaaaaaaaaaaaa, bbbbbbbbbbbbbbbbbbbbbbbbbbbbbb, cccccccccccccccccccccccccccccc);
+}
+";
+    let output =
+        convert_syntax(input, Syntax::Scss, Syntax::Sass, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        output,
+        "\
+a
+  transition: opacity 0.3s ease-in-out, transform 0.3s ease-in-out, visibility 0.3s linear
+  @include foo(aaaaaaaaaaaaaaaaaaaa, bbbbbbbbbbbbbbbbbbbbbbbbbbbbbb, cccccccccccccccccccccccccccccc)
+"
+    );
+}
+
+#[test]
+fn multi_line_values_to_sass() {
+    let input = "\
+a {
+  grid-template-areas:
+    \"header header\"
+    \"main sidebar\";
+}
+";
+    assert!(matches!(
+        convert_syntax(input, Syntax::Scss, Syntax::Sass, &FormatOptions::default()),
+        Err(Error::UnsupportedConversion {
+            from: Syntax::Scss,
+            to: Syntax::Sass
+        })
+    ));
+}
+
+#[test]
+fn unsupported_conversion() {
+    assert!(matches!(
+        convert_syntax(
+            "a {}",
+            Syntax::Scss,
+            Syntax::Less,
+            &FormatOptions::default()
+        ),
+        Err(Error::UnsupportedConversion {
+            from: Syntax::Scss,
+            to: Syntax::Less
+        })
+    ));
+}
@@ -0,0 +1,73 @@
+use malva::{
+    config::{LineBreak, PartialFormatOptions},
+    config_file::{resolve_options, resolve_options_with_fallback},
+    editorconfig::layout_options,
+};
+use std::fs;
+
+#[test]
+fn sections_and_precedence() {
+    let dir = std::env::temp_dir().join(format!("malva_editorconfig_test_{}", std::process::id()));
+    fs::create_dir_all(dir.join("sub/configured")).unwrap();
+    fs::write(
+        dir.join(".editorconfig"),
+        r#"
+root = true
+
+[*]
+indent_style = space
+indent_size = 4
+end_of_line = crlf
+max_line_length = 100
+
+[*.{scss,less}]
+indent_style = tab
+indent_size = tab
+tab_width = 8
+
+[/sub/**.css]
+max_line_length = off
+"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("sub/.editorconfig"),
+        "[*.css]\nindent_size = 3\nend_of_line = unset\n",
+    )
+    .unwrap();
+    fs::write(dir.join("sub/configured/malva.toml"), "indentWidth = 6\n").unwrap();
+
+    let options = layout_options(dir.join("a.css"));
+    assert_eq!(options.use_tabs, Some(false));
+    assert_eq!(options.indent_width, Some(4));
+    assert!(matches!(options.line_break, Some(LineBreak::Crlf)));
+    assert_eq!(options.print_width, Some(100));
+
+    let options = layout_options(dir.join("a.scss"));
+    assert_eq!(options.use_tabs, Some(true));
+    assert_eq!(options.indent_width, Some(8));
+
+    let options = layout_options(dir.join("sub/a.css"));
+    assert_eq!(options.indent_width, Some(3));
+    assert!(options.line_break.is_none());
+    assert!(options.print_width.is_none());
+
+    let options = resolve_options(dir.join("sub/configured/a.css")).unwrap();
+    assert_eq!(options.layout.indent_width, 6);
+    assert!(matches!(options.layout.line_break, LineBreak::Lf));
+    assert_eq!(options.layout.print_width, 80);
+
+    let fallback = PartialFormatOptions {
+        use_tabs: Some(true),
+        indent_width: Some(2),
+        print_width: Some(120),
+        ..Default::default()
+    };
+    let options =
+        resolve_options_with_fallback(dir.join("sub/configured/a.css"), &fallback).unwrap();
+    assert_eq!(options.layout.indent_width, 6);
+    assert!(!options.layout.use_tabs);
+    assert_eq!(options.layout.print_width, 120);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,195 @@
+use malva::{
+    Error,
+    config::FormatOptions,
+    embedded::{format_markdown, format_markup, format_template_literal},
+};
+
+#[test]
+fn vue_sfc() {
+    let input = r#"<template>
+  <div style="color:red;margin:0 auto" :style="{ color }">{{ msg }}</div>
+</template>
+
+<script>
+const style = '<style>a{}</style>'
+</script>
+
+<style scoped lang="scss">
+.a{.b{color:red}}
+</style>
+
+<style lang="stylus">
+.a
+  color red
+</style>
+"#;
+    let output = format_markup(input, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        output,
+        r#"<template>
+  <div style="color: red; margin: 0 auto" :style="{ color }">{{ msg }}</div>
+</template>
+
+<script>
+const style = '<style>a{}</style>'
+</script>
+
+<style scoped lang="scss">
+.a {
+  .b {
+    color: red;
+  }
+}
+</style>
+
+<style lang="stylus">
+.a
+  color red
+</style>
+"#
+    );
+}
+
+#[test]
+fn html_indentation() {
+    let input = "<html>\n  <head>\n    <style>\n      a{color:red}\n      b{content:\"b\"}\n    </style>\n  </head>\n  <body style='font-family:\"Fira Code\"'><style>c{}</style></body>\n</html>\n";
+    let output = format_markup(input, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        output,
+        "<html>\n  <head>\n    <style>\n      a {\n        color: red;\n      }\n      b {\n        content: \"b\";\n      }\n    </style>\n  </head>\n  <body style='font-family: \"Fira Code\"'><style>\nc {}\n</style></body>\n</html>\n"
+    );
+}
+
+#[test]
+fn svelte_expressions() {
+    let input = "<div style=\"color: {color}\" {...props}></div>\n<!-- <style>a{}</style> -->\n<style>\n  a{color:red}\n</style>\n";
+    let output = format_markup(input, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        output,
+        "<div style=\"color: {color}\" {...props}></div>\n<!-- <style>a{}</style> -->\n<style>\n  a {\n    color: red;\n  }\n</style>\n"
+    );
+}
+
+#[test]
+fn error_location() {
+    let input = "<p>\n</p>\n<style>\na {\n}}\n</style>\n";
+    let error = format_markup(input, &FormatOptions::default()).unwrap_err();
+    assert!(matches!(error, Error::Parser(_, 5, 2)));
+    assert_eq!(error.span().map(|span| span.start), Some(22));
+}
+
+#[test]
+fn template_literal() {
+    let quasis = [
+        "\n  color:",
+        ";\n  margin:0 ",
+        "px;\n  ",
+        "\n  ",
+        ";\n  ",
+        ":hover{",
+        ":red}\n",
+    ];
+    let expressions = [
+        "props.color",
+        "gap",
+        "mixin",
+        "css`a: b;`",
+        "Button",
+        "prop",
+    ];
+    let output = format_template_literal(&quasis, &expressions, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        output,
+        "\n  color: ${props.color};\n  margin: 0 ${gap}px;\n  ${mixin}\n  ${css`a: b;`};\n  ${Button}:hover {\n    ${prop}: red;\n  }\n"
+    );
+}
+
+#[test]
+fn template_literal_error() {
+    let error = format_template_literal(
+        &["\n  color: ", ";\n  a{}}\n"],
+        &["red"],
+        &FormatOptions::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(error, Error::Parser(_, 3, 6)));
+    assert!(matches!(
+        format_template_literal(&["a"], &["b"], &FormatOptions::default()),
+        Err(Error::MismatchedTemplate {
+            quasis: 1,
+            expressions: 1
+        })
+    ));
+}
+
+#[test]
+fn markdown() {
+    let input = "# Colors
+
+```scss
+.a{.b{color:red}}
+```
+
+- item
+
+  ~~~less title=\"mixin\"
+  .m(){color:red}
+  ~~~
+
+```js
+a{color:red}
+```
+
+```css
+a{color:red
+```
+";
+    let (output, warnings) = format_markdown(input, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        output,
+        "# Colors
+
+```scss
+.a {
+  .b {
+    color: red;
+  }
+}
+```
+
+- item
+
+  ~~~less title=\"mixin\"
+  .m() {
+    color: red;
+  }
+  ~~~
+
+```js
+a{color:red}
+```
+
+```css
+a{color:red
+```
+"
+    );
+    assert_eq!(warnings.len(), 1);
+    assert_eq!((warnings[0].line, warnings[0].col), (19, 1));
+}
+
+#[test]
+fn markdown_kept_blocks() {
+    let input = "    ```css
+    a{color:red}
+    ```
+
+```css
+/* malva-config {\"indentWidth\":0} */
+a{color:red}
+```
+";
+    let (output, warnings) = format_markdown(input, &FormatOptions::default()).unwrap();
+    assert_eq!(output, input);
+    assert!(warnings.is_empty());
+}
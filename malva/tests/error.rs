@@ -0,0 +1,111 @@
+use malva::{
+    ColumnUnit, Error, Syntax, config::FormatOptions, format_text, format_text_edits,
+    format_text_with_cursor, format_text_with_diagnostics,
+};
+
+#[test]
+fn render_code_frame() {
+    let source = "a {\n  color: red;\n}}\n";
+    let error = format_text(source, Syntax::Css, &FormatOptions::default()).unwrap_err();
+    assert_eq!(error.span().map(|span| span.start), Some(19));
+    assert_eq!(
+        error.render(source),
+        "error: syntax error at line 3, col 2: expect token `<eof>`, but found `}`
+  |
+3 | }}
+  |  ^
+"
+    );
+}
+
+#[test]
+fn invalid_options() {
+    let mut options = FormatOptions::default();
+    options.layout.indent_width = 0;
+    let error = format_text("a {}", Syntax::Css, &options).unwrap_err();
+    assert!(matches!(error, Error::InvalidOptions(..)));
+    assert!(error.span().is_none());
+    assert_eq!(
+        error.render("a {}"),
+        "error: invalid options: `indentWidth` must be greater than 0\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "config_serde")]
+fn invalid_options_in_config_comment() {
+    let source = "/* malva-config { \"indentWidth\": \"4\" } */\na {}";
+    let error = format_text(source, Syntax::Css, &FormatOptions::default()).unwrap_err();
+    assert!(matches!(error, Error::InvalidOptions(..)));
+
+    let source = "/* malva-config { \"indentWidth\": 0 } */\na {}";
+    let error = format_text(source, Syntax::Css, &FormatOptions::default()).unwrap_err();
+    assert_eq!(
+        error.render(source),
+        "error: invalid options: `indentWidth` must be greater than 0\n"
+    );
+}
+
+#[test]
+fn column_unit() {
+    let source = "/* 😀 */ a {}}";
+    let error = format_text(source, Syntax::Css, &FormatOptions::default()).unwrap_err();
+    assert!(matches!(error, Error::Parser(_, 1, 16)));
+    assert_eq!(error.line_col(source, ColumnUnit::Utf8), Some((1, 16)));
+    assert_eq!(error.line_col(source, ColumnUnit::Utf16), Some((1, 14)));
+    assert_eq!(error.line_col(source, ColumnUnit::Char), Some((1, 13)));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn lost_comment_in_debug_build() {
+    let source = "a {\n  color: red !/* lost */ important;\n}\n";
+    let error = format_text(source, Syntax::Css, &FormatOptions::default()).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::LostComment {
+            line: 2,
+            col: 15,
+            ..
+        }
+    ));
+}
+
+#[test]
+#[cfg(feature = "verify")]
+fn keep_comments_in_an_plus_b() {
+    let options = FormatOptions {
+        verify: true,
+        ..Default::default()
+    };
+    let output = format_text(
+        "a:nth-child(2n /* keep me */ + 1) {}\n",
+        Syntax::Css,
+        &options,
+    )
+    .unwrap();
+    assert_eq!(output, "a:nth-child(2n+1 /* keep me */) {}\n");
+}
+
+#[test]
+#[cfg(feature = "verify")]
+fn verify_in_all_entry_points() {
+    // condition is broken into lines, which is invalid in indented Sass
+    let source = "a\n  @if $a == 1\n    color: red\n";
+    let mut options = FormatOptions::default();
+    options.layout.print_width = 10;
+    options.verify = true;
+    let is_unstable = |error| matches!(error, Error::UnstableOutput { line: 2, .. });
+    assert!(is_unstable(
+        format_text(source, Syntax::Sass, &options).unwrap_err()
+    ));
+    assert!(is_unstable(
+        format_text_with_diagnostics(source, Syntax::Sass, &options).unwrap_err()
+    ));
+    assert!(is_unstable(
+        format_text_edits(source, Syntax::Sass, &options).unwrap_err()
+    ));
+    assert!(is_unstable(
+        format_text_with_cursor(source, Syntax::Sass, &options, 0).unwrap_err()
+    ));
+}
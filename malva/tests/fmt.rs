@@ -36,7 +36,7 @@ fn fmt_snapshot() {
                 )
             })
             .unwrap();
-        let options = if let Some(config) = comments
+        let mut options = if let Some(config) = comments
             .first()
             .and_then(|comment| comment.content.trim_start().strip_prefix("cfg"))
         {
@@ -44,8 +44,11 @@ fn fmt_snapshot() {
         } else {
             Default::default()
         };
+        options.verify = true;
 
-        let output = format_text(&input, syntax, &options).unwrap();
+        let output = format_text(&input, syntax, &options)
+            .map_err(|error| format!("'{}' {}", path.display(), error.render(&input)))
+            .unwrap();
         similar_asserts::assert_eq!(
             output,
             output
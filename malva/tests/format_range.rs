@@ -0,0 +1,56 @@
+use malva::{Syntax, config::FormatOptions, format_range};
+
+#[test]
+fn format_top_level_statements() {
+    let input = "a{color:red}\nb{color:red}\nc{color:red}\n";
+    let output = format_range(input, Syntax::Css, &FormatOptions::default(), 14..16).unwrap();
+    assert_eq!(
+        output,
+        "a{color:red}\nb {\n  color: red;\n}\nc{color:red}\n"
+    );
+}
+
+#[test]
+fn format_nested_statements() {
+    let input = "a {\n    b{color:red;}\n    c{color:red;}\n}\n";
+    let output = format_range(input, Syntax::Scss, &FormatOptions::default(), 8..10).unwrap();
+    assert_eq!(
+        output,
+        "a {\n    b {\n      color: red;\n    }\n    c{color:red;}\n}\n"
+    );
+}
+
+#[test]
+fn format_declarations_with_semicolon() {
+    let input = "a {\n  color:red ;\n  margin:0;\n  padding:0\n}\n";
+    let output = format_range(input, Syntax::Css, &FormatOptions::default(), 6..25).unwrap();
+    assert_eq!(output, "a {\n  color: red;\n  margin: 0;\n  padding:0\n}\n");
+}
+
+#[test]
+#[cfg(feature = "config_serde")]
+fn format_with_overridden_options() {
+    let input = "/* malva-set quotes=\"alwaysSingle\" */\na {\n  b{content:\"b\"}\n}\n";
+    let output = format_range(input, Syntax::Scss, &FormatOptions::default(), 44..46).unwrap();
+    assert_eq!(
+        output,
+        "/* malva-set quotes=\"alwaysSingle\" */\na {\n  b {\n    content: 'b';\n  }\n}\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "config_serde")]
+fn format_with_overridden_print_width() {
+    let input = "a{grid-area:1/2/3/4}\n/* malva-set printWidth=20 */\nb{grid-template-columns:[a] 1fr [b] 1fr}\n";
+    let output = format_range(
+        input,
+        Syntax::Css,
+        &FormatOptions::default(),
+        0..input.len(),
+    )
+    .unwrap();
+    assert_eq!(
+        output,
+        "a {\n  grid-area: 1/2/3/4;\n}\n/* malva-set printWidth=20 */\nb {\n  grid-template-columns:\n    [a] 1fr [b] 1fr;\n}\n"
+    );
+}
@@ -0,0 +1,41 @@
+use insta::glob;
+use malva::{config::FormatOptions, format_text, format_text_edits};
+use raffia::Syntax;
+use std::fs;
+
+#[test]
+fn apply_edits() {
+    glob!("fmt/**/*.{css,scss,sass,less}", |path| {
+        let input = fs::read_to_string(path).unwrap();
+        let syntax = match path.extension().unwrap().to_str().unwrap() {
+            "css" => Syntax::Css,
+            "scss" => Syntax::Scss,
+            "sass" => Syntax::Sass,
+            "less" => Syntax::Less,
+            _ => unreachable!("unknown file extension"),
+        };
+        let options = FormatOptions::default();
+
+        let output = format_text(&input, syntax, &options).unwrap();
+        let edits = format_text_edits(&input, syntax, &options).unwrap();
+        let mut applied = input.clone();
+        for edit in edits.iter().rev() {
+            applied.replace_range(edit.range.clone(), &edit.new_text);
+        }
+        similar_asserts::assert_eq!(
+            output,
+            applied,
+            "edits of '{}' are incorrect",
+            path.display()
+        );
+    });
+}
+
+#[test]
+fn minimal_edits() {
+    let input = "a {\n  color: red;\n}\n\nb {\n  color:blue;\n}\n";
+    let edits = format_text_edits(input, Syntax::Css, &FormatOptions::default()).unwrap();
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].range, 33..33);
+    assert_eq!(edits[0].new_text, " ");
+}
@@ -0,0 +1,89 @@
+use malva::{Error, Syntax, config::FormatOptions, format_text_tolerant};
+
+#[test]
+fn keep_unparseable_statements() {
+    let input = "a{color:red}\n\nb{color:red}}\nc{color:blue}\n";
+    let (output, errors) = format_text_tolerant(input, Syntax::Css, &FormatOptions::default());
+    assert_eq!(
+        output,
+        "a {\n  color: red;\n}\n\nb {\n  color: red;\n}\n}\nc {\n  color: blue;\n}\n"
+    );
+    assert!(matches!(&errors[..], [Error::Parser(_, 3, _)]));
+}
+
+#[test]
+fn sass_unparseable_statements() {
+    let input = "a\n    color: red\nb\n  color: (\nc\n    color: blue\n";
+    let (output, errors) = format_text_tolerant(input, Syntax::Sass, &FormatOptions::default());
+    assert_eq!(output, "a\n  color: red\nb\n  color: (\nc\n  color: blue\n");
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+#[cfg(feature = "verify")]
+fn return_verify_errors() {
+    let options = FormatOptions {
+        verify: true,
+        ..Default::default()
+    };
+    let input = "a{color:red!/*x*/important}\n";
+    let (output, errors) = format_text_tolerant(input, Syntax::Css, &options);
+    assert_eq!(output, input);
+    assert!(matches!(&errors[..], [Error::LostComment { line: 1, .. }]));
+}
+
+#[test]
+#[cfg(feature = "config_serde")]
+fn directives_with_syntax_errors() {
+    let input = "/* malva-config {\"indentWidth\": 4} */\na{color:red}\nb{color:red}}\n";
+    let (output, errors) = format_text_tolerant(input, Syntax::Css, &FormatOptions::default());
+    assert_eq!(
+        output,
+        "/* malva-config {\"indentWidth\": 4} */\na {\n    color: red;\n}\nb {\n    color: red;\n}\n}\n"
+    );
+    assert_eq!(errors.len(), 1);
+
+    // invalid options are returned whether there're syntax errors or not
+    let input = "/* malva-config {\"indentWidth\": [] } */\na{color:red}\n";
+    let (output, errors) = format_text_tolerant(input, Syntax::Css, &FormatOptions::default());
+    assert_eq!(output, input);
+    assert!(matches!(&errors[..], [Error::InvalidOptions(_)]));
+    let input = "/* malva-config {\"indentWidth\": [] } */\na{color:red}}\n";
+    let (output, errors) = format_text_tolerant(input, Syntax::Css, &FormatOptions::default());
+    assert_eq!(output, input);
+    assert!(matches!(&errors[..], [Error::InvalidOptions(_)]));
+}
+
+#[test]
+fn ignore_file_with_syntax_errors() {
+    let input = "/* malva-ignore-file */\na{color:red}\nb{color:red}}\n";
+    let (output, errors) = format_text_tolerant(input, Syntax::Css, &FormatOptions::default());
+    assert_eq!(output, input);
+    assert!(errors.is_empty());
+}
+
+#[test]
+#[cfg(feature = "verify")]
+fn verify_each_chunk() {
+    let options = FormatOptions {
+        verify: true,
+        ..Default::default()
+    };
+    let input = "a{color:red}}\n\nb{color:red!/*x*/important}\n";
+    let (output, errors) = format_text_tolerant(input, Syntax::Css, &options);
+    assert_eq!(
+        output,
+        "a {\n  color: red;\n}\n}\n\nb{color:red!/*x*/important}\n"
+    );
+    assert!(matches!(
+        &errors[..],
+        [
+            Error::Parser(_, 1, _),
+            Error::LostComment {
+                line: 3,
+                col: 13,
+                ..
+            }
+        ]
+    ));
+}
@@ -0,0 +1,77 @@
+use malva::{Syntax, config::FormatOptions, format_text_with_cursor};
+
+#[test]
+fn cursor_before_edit() {
+    let input = "a {\n  color: red;\n}\nb{color:blue}\n";
+    let (output, cursor) =
+        format_text_with_cursor(input, Syntax::Css, &FormatOptions::default(), 9).unwrap();
+    assert_eq!(&output[cursor..], "or: red;\n}\nb {\n  color: blue;\n}\n");
+}
+
+#[test]
+fn cursor_after_edit() {
+    let input = "a{color:red}\nb {\n  color: blue;\n}\n";
+    let (output, cursor) =
+        format_text_with_cursor(input, Syntax::Css, &FormatOptions::default(), 26).unwrap();
+    assert_eq!(&output[cursor..], "blue;\n}\n");
+}
+
+#[test]
+fn cursor_inside_edit() {
+    let input = "a{color:red}\n";
+    let (output, cursor) =
+        format_text_with_cursor(input, Syntax::Css, &FormatOptions::default(), 8).unwrap();
+    assert_eq!(&output[cursor..], "red;\n}\n");
+}
+
+#[test]
+fn cursor_inside_multi_byte_char() {
+    let input = "a{content:\"中文\"}\n";
+    // the 2nd byte of `文`
+    let (output, cursor) =
+        format_text_with_cursor(input, Syntax::Css, &FormatOptions::default(), 15).unwrap();
+    assert_eq!(&output[cursor..], "文\";\n}\n");
+
+    let input = "a {\n  content: \"中文\";\n}\n";
+    let (output, cursor) =
+        format_text_with_cursor(input, Syntax::Css, &FormatOptions::default(), 21).unwrap();
+    assert_eq!(output, input);
+    assert_eq!(&output[cursor..], "文\";\n}\n");
+}
+
+#[test]
+fn cursor_after_inserted_or_removed_chars() {
+    // leading zero is inserted
+    let input = "a{width:.5em}\n";
+    let (output, cursor) =
+        format_text_with_cursor(input, Syntax::Css, &FormatOptions::default(), 10).unwrap();
+    assert_eq!(&output[cursor..], "em;\n}\n");
+
+    // quotes are replaced
+    let input = "a{content:'x'}\n";
+    let (output, cursor) =
+        format_text_with_cursor(input, Syntax::Css, &FormatOptions::default(), 11).unwrap();
+    assert_eq!(&output[cursor..], "x\";\n}\n");
+
+    // redundant semicolon is removed
+    let input = "a{color:red;;top:0}\n";
+    let (output, cursor) =
+        format_text_with_cursor(input, Syntax::Css, &FormatOptions::default(), 13).unwrap();
+    assert_eq!(&output[cursor..], "top: 0;\n}\n");
+}
+
+#[test]
+fn cursor_inside_reordered_declarations() {
+    // cursor is at `color`, but it's only approximate after sorting
+    let input = "a{color:red;width:0}\n";
+    let options = FormatOptions {
+        language: malva::config::LanguageOptions {
+            declaration_order: Some(malva::config::DeclarationOrder::Smacss),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (output, cursor) = format_text_with_cursor(input, Syntax::Css, &options, 2).unwrap();
+    assert_eq!(output, "a {\n  width: 0;\n  color: red;\n}\n");
+    assert_eq!(&output[cursor..], "width: 0;\n  color: red;\n}\n");
+}
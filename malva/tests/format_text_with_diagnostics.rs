@@ -0,0 +1,57 @@
+use malva::{Syntax, config::FormatOptions, format_text_with_diagnostics};
+
+#[test]
+fn top_level_declarations() {
+    let input = "a{color:red}\ncolor: red;\n";
+    let (output, warnings) =
+        format_text_with_diagnostics(input, Syntax::Css, &FormatOptions::default()).unwrap();
+    assert_eq!(output, "a {\n  color: red;\n}\ncolor: red;\n");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!((warnings[0].line, warnings[0].col), (2, 1));
+}
+
+#[test]
+fn no_warnings() {
+    let input = "a{color:red}\n";
+    let (_, warnings) =
+        format_text_with_diagnostics(input, Syntax::Css, &FormatOptions::default()).unwrap();
+    assert!(warnings.is_empty());
+}
+
+#[test]
+#[cfg(feature = "config_serde")]
+fn invalid_directives() {
+    use malva::WarningKind;
+
+    let input = "/* malva-set quotes */\na{content:\"a\"}\nb{\n  /* malva-set printWidth=20 */\n  c{content:\"c\"}\n}\n";
+    let (_, warnings) =
+        format_text_with_diagnostics(input, Syntax::Css, &FormatOptions::default()).unwrap();
+    assert_eq!(warnings.len(), 2);
+    assert!(matches!(
+        warnings[0].kind,
+        WarningKind::InvalidDirective(..)
+    ));
+    assert_eq!((warnings[0].line, warnings[0].col), (1, 1));
+    assert!(matches!(
+        warnings[1].kind,
+        WarningKind::InvalidDirective(..)
+    ));
+    assert_eq!((warnings[1].line, warnings[1].col), (4, 3));
+}
+
+#[test]
+#[cfg(feature = "config_serde")]
+fn unknown_option_in_directive() {
+    use malva::WarningKind;
+    use std::fs;
+
+    let input = fs::read_to_string("tests/fmt/css/options-override/default.css").unwrap();
+    let (_, warnings) =
+        format_text_with_diagnostics(&input, Syntax::Css, &FormatOptions::default()).unwrap();
+    // the other one is from overriding print width in nested block
+    assert_eq!(warnings.len(), 2);
+    assert!(
+        matches!(&warnings[0].kind, WarningKind::InvalidDirective(message) if message == "unknown option `printWdith`")
+    );
+    assert_eq!((warnings[0].line, warnings[0].col), (26, 1));
+}
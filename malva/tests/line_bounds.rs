@@ -0,0 +1,45 @@
+use malva::{ColumnUnit, LineBounds};
+
+#[test]
+fn convert_in_different_units() {
+    let input = "a {}\n/* 中文 😀 */ b {}\n";
+    let line_bounds = LineBounds::new(input);
+    let offset = input.find('b').unwrap();
+
+    assert_eq!(line_bounds.line_col(input, 0, ColumnUnit::Utf8), (1, 1));
+    assert_eq!(line_bounds.line_col(input, 4, ColumnUnit::Utf8), (1, 5));
+    assert_eq!(
+        line_bounds.line_col(input, offset, ColumnUnit::Utf8),
+        (2, 19)
+    );
+    assert_eq!(
+        line_bounds.line_col(input, offset, ColumnUnit::Utf16),
+        (2, 13)
+    );
+    assert_eq!(
+        line_bounds.line_col(input, offset, ColumnUnit::Char),
+        (2, 12)
+    );
+
+    for unit in [ColumnUnit::Utf8, ColumnUnit::Utf16, ColumnUnit::Char] {
+        let (line, col) = line_bounds.line_col(input, offset, unit);
+        assert_eq!(line_bounds.offset(input, line, col, unit), offset);
+    }
+}
+
+#[test]
+fn clamp_out_of_range() {
+    let input = "a {}\n😀 {}";
+    let line_bounds = LineBounds::new(input);
+
+    assert_eq!(line_bounds.line_col(input, 100, ColumnUnit::Char), (2, 5));
+    // in the middle of a char
+    assert_eq!(line_bounds.line_col(input, 7, ColumnUnit::Utf16), (2, 1));
+    assert_eq!(line_bounds.offset(input, 2, 2, ColumnUnit::Utf16), 5);
+
+    assert_eq!(line_bounds.offset(input, 1, 100, ColumnUnit::Utf8), 4);
+    assert_eq!(
+        line_bounds.offset(input, 100, 1, ColumnUnit::Utf8),
+        input.len()
+    );
+}